@@ -7,10 +7,48 @@ struct Mapping {
     dest_start: u64,
 }
 
-struct Mappings {
+pub struct Mappings {
     maps: Vec<Mapping>,
 }
 
+/// A problem found in a mapping block. Ranges are half open `[start, end)`
+/// and lines are indices into the mappings of the block.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Diagnostic {
+    /// Two lines map the same source numbers, so only the first one is used.
+    SourceOverlap {
+        first: usize,
+        second: usize,
+        start: u64,
+        end: u64,
+    },
+    /// Two lines map onto the same destination numbers, so the map is not injective.
+    DestinationOverlap {
+        first: usize,
+        second: usize,
+        start: u64,
+        end: u64,
+    },
+    /// Source numbers between the mapped ranges that fall through unchanged.
+    Gap { start: u64, end: u64 },
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum MappingError {
+    Parse(std::num::ParseIntError),
+    /// A line that is neither a `... map:` header nor three numbers.
+    Malformed(String),
+    /// The block contains overlapping source or destination ranges and mapping forwards or
+    /// backwards depends on line order.
+    Ambiguous(Vec<Diagnostic>),
+}
+
+impl From<std::num::ParseIntError> for MappingError {
+    fn from(e: std::num::ParseIntError) -> Self {
+        MappingError::Parse(e)
+    }
+}
+
 #[derive(Debug)]
 struct Range {
     start: u64,
@@ -47,6 +85,90 @@ impl Mappings {
             .find_map(|m| m.reverse(index))
             .unwrap_or(*index)
     }
+
+    /// Like `from_str`, but fails on lines other than the `... map:` header and mappings instead
+    /// of skipping them, and rejects blocks whose source or destination ranges overlap, so that
+    /// both `get` and `reverse` are independent of line order.
+    pub fn from_str_strict(s: &str) -> Result<Self, MappingError> {
+        let mut maps = Vec::new();
+        for line in s.lines().filter(|l| !l.is_empty() && !l.ends_with(" map:")) {
+            if line.split(' ').count() != 3 {
+                return Err(MappingError::Malformed(line.to_string()));
+            }
+            maps.push(Mapping::from_str(line)?);
+        }
+        let mappings = Mappings { maps };
+        let overlaps: Vec<Diagnostic> = mappings
+            .diagnostics()
+            .into_iter()
+            .filter(|d| !matches!(d, Diagnostic::Gap { .. }))
+            .collect();
+        if overlaps.is_empty() {
+            Ok(mappings)
+        } else {
+            Err(MappingError::Ambiguous(overlaps))
+        }
+    }
+
+    /// Reports overlapping source ranges, overlapping destination ranges and
+    /// gaps between the source ranges.
+    pub fn diagnostics(&self) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        for (i, a) in self.maps.iter().enumerate() {
+            for (j, b) in self.maps.iter().enumerate().skip(i + 1) {
+                if let Some(r) = intersect(&a.source(), &b.source()) {
+                    diagnostics.push(Diagnostic::SourceOverlap {
+                        first: i,
+                        second: j,
+                        start: r.start,
+                        end: r.end,
+                    });
+                }
+                if let Some(r) = intersect(&a.dest(), &b.dest()) {
+                    diagnostics.push(Diagnostic::DestinationOverlap {
+                        first: i,
+                        second: j,
+                        start: r.start,
+                        end: r.end,
+                    });
+                }
+            }
+        }
+        diagnostics.extend(self.gaps());
+        diagnostics
+    }
+
+    fn gaps(&self) -> Vec<Diagnostic> {
+        let mut sources: Vec<Range> = self.maps.iter().map(|m| m.source()).collect();
+        sources.sort_by_key(|r| r.start);
+        let mut gaps = Vec::new();
+        let mut covered_until: Option<u64> = None;
+        for r in sources {
+            match covered_until {
+                Some(end) if end < r.start => {
+                    gaps.push(Diagnostic::Gap {
+                        start: end,
+                        end: r.start,
+                    });
+                    covered_until = Some(r.end);
+                }
+                Some(end) => covered_until = Some(end.max(r.end)),
+                None => covered_until = Some(r.end),
+            }
+        }
+        gaps
+    }
+}
+
+/// Intersection of two half open ranges.
+fn intersect(a: &Range, b: &Range) -> Option<Range> {
+    let start = a.start.max(b.start);
+    let end = a.end.min(b.end);
+    if start < end {
+        Some(Range { start, end })
+    } else {
+        None
+    }
 }
 
 impl FromStr for Mapping {
@@ -70,6 +192,20 @@ impl FromStr for Mapping {
 }
 
 impl Mapping {
+    fn source(&self) -> Range {
+        Range {
+            start: self.source_start,
+            end: self.source_end,
+        }
+    }
+
+    fn dest(&self) -> Range {
+        Range {
+            start: self.dest_start,
+            end: self.dest_start + self.source_end - self.source_start,
+        }
+    }
+
     fn get(&self, index: &u64) -> Option<u64> {
         if self.source_start <= *index && *index < self.source_end {
             Some(index - self.source_start + self.dest_start)
@@ -150,7 +286,7 @@ pub fn process_part2(input: &str) -> u64 {
             let prev =
                 seed_to_soil.reverse(&soil_to_fertilizer.reverse(&fertilizer_to_water.reverse(
                     &water_to_light.reverse(&light_to_temperature.reverse(
-                        &temperature_to_humidity.reverse(&humidity_to_location.reverse(&n)),
+                        &temperature_to_humidity.reverse(&humidity_to_location.reverse(n)),
                     )),
                 )));
             seeds.iter().any(|sr| sr.contrains(&prev))
@@ -253,4 +389,64 @@ humidity-to-location map:
 56 93 4";
         assert_eq!(46_u64, process_part2(input));
     }
+
+    #[test]
+    fn diagnostics() {
+        let mappings = Mappings::from_str("50 98 2\n52 50 48").unwrap();
+        assert_eq!(mappings.diagnostics(), vec![]);
+
+        let mappings = Mappings::from_str("0 10 5\n20 12 5\n2 20 4").unwrap();
+        assert_eq!(
+            mappings.diagnostics(),
+            vec![
+                Diagnostic::SourceOverlap {
+                    first: 0,
+                    second: 1,
+                    start: 12,
+                    end: 15
+                },
+                Diagnostic::DestinationOverlap {
+                    first: 0,
+                    second: 2,
+                    start: 2,
+                    end: 5
+                },
+                Diagnostic::Gap { start: 17, end: 20 },
+            ]
+        );
+    }
+
+    #[test]
+    fn strict_parsing() {
+        assert!(Mappings::from_str_strict("0 10 5\n5 15 5").is_ok());
+        assert!(Mappings::from_str_strict("seed-to-soil map:\n0 10 5\n").is_ok());
+        assert!(matches!(
+            Mappings::from_str_strict("0 10 5\n5 x 5"),
+            Err(MappingError::Parse(_))
+        ));
+        assert_eq!(
+            Mappings::from_str_strict("0 10 5\n0 10").err(),
+            Some(MappingError::Malformed(String::from("0 10")))
+        );
+        assert_eq!(
+            Mappings::from_str_strict("0 10 5\n0 15 5").err(),
+            Some(MappingError::Ambiguous(vec![
+                Diagnostic::DestinationOverlap {
+                    first: 0,
+                    second: 1,
+                    start: 0,
+                    end: 5
+                }
+            ]))
+        );
+        assert_eq!(
+            Mappings::from_str_strict("0 10 5\n20 12 5").err(),
+            Some(MappingError::Ambiguous(vec![Diagnostic::SourceOverlap {
+                first: 0,
+                second: 1,
+                start: 12,
+                end: 15
+            }]))
+        );
+    }
 }
@@ -0,0 +1,197 @@
+use num::{BigUint, ToPrimitive};
+
+use crate::winning_interval;
+
+/// How far a boat travels in a race of `time` milliseconds when the button is held for `hold`.
+///
+/// The distance has to rise up to `best_hold` and fall after it, so that the winning hold
+/// times form one interval around it.
+pub trait BoatModel {
+    fn distance(&self, hold: u64, time: u64) -> u128;
+
+    /// Hold time with the largest distance. Defaults to a ternary search, which needs the
+    /// distance to be strictly rising before and strictly falling after the best hold time.
+    fn best_hold(&self, time: u64) -> u64 {
+        let (mut lo, mut hi) = (0, time);
+        while hi - lo > 2 {
+            let m1 = lo + (hi - lo) / 3;
+            let m2 = hi - (hi - lo) / 3;
+            if self.distance(m1, time) < self.distance(m2, time) {
+                lo = m1 + 1;
+            } else {
+                hi = m2;
+            }
+        }
+        (lo..=hi)
+            .max_by_key(|hold| self.distance(*hold, time))
+            .unwrap()
+    }
+
+    /// First and last hold time beating `record`. Both bounds are found by bisection on the
+    /// two monotone sides of `best_hold`.
+    fn winning_interval(&self, time: u64, record: u64) -> Option<(u64, u64)> {
+        let record = record as u128;
+        let best = self.best_hold(time);
+        if self.distance(best, time) <= record {
+            return None;
+        }
+        let first = partition_point(0, best, |hold| self.distance(hold, time) <= record);
+        let last = if self.distance(time, time) > record {
+            time
+        } else {
+            partition_point(best, time, |hold| self.distance(hold, time) > record) - 1
+        };
+        Some((first, last))
+    }
+
+    fn count_wins(&self, time: u64, record: u64) -> u64 {
+        self.winning_interval(time, record)
+            .map(|(first, last)| last - first + 1)
+            .unwrap_or(0)
+    }
+}
+
+/// First value in `lo..hi` for which `pred` is false. `pred` has to be true before that value
+/// and false after it.
+fn partition_point(mut lo: u64, mut hi: u64, pred: impl Fn(u64) -> bool) -> u64 {
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        if pred(mid) {
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
+    }
+    lo
+}
+
+/// The speed equals the hold time. This is the model of the puzzle.
+pub struct Linear;
+
+impl BoatModel for Linear {
+    fn distance(&self, hold: u64, time: u64) -> u128 {
+        hold as u128 * time.saturating_sub(hold) as u128
+    }
+
+    fn best_hold(&self, time: u64) -> u64 {
+        time / 2
+    }
+
+    fn winning_interval(&self, time: u64, record: u64) -> Option<(u64, u64)> {
+        winning_interval(&BigUint::from(time), &BigUint::from(record)).map(|interval| {
+            (
+                interval.first.to_u64().unwrap(),
+                interval.last.to_u64().unwrap(),
+            )
+        })
+    }
+}
+
+/// The speed grows with `hold^exponent`, so longer charging pays off more than linearly.
+/// Distances beyond `u128::MAX` saturate, which beats every record.
+pub struct Accelerating {
+    pub exponent: u32,
+}
+
+impl BoatModel for Accelerating {
+    fn distance(&self, hold: u64, time: u64) -> u128 {
+        let moving = time.saturating_sub(hold) as u128;
+        if moving == 0 {
+            return 0;
+        }
+        (hold as u128)
+            .checked_pow(self.exponent)
+            .and_then(|speed| speed.checked_mul(moving))
+            .unwrap_or(u128::MAX)
+    }
+}
+
+/// The speed equals the hold time, but can not exceed `max_speed`.
+pub struct SpeedCap {
+    pub max_speed: u64,
+}
+
+impl BoatModel for SpeedCap {
+    fn distance(&self, hold: u64, time: u64) -> u128 {
+        hold.min(self.max_speed) as u128 * time.saturating_sub(hold) as u128
+    }
+
+    fn best_hold(&self, time: u64) -> u64 {
+        (time / 2).min(self.max_speed)
+    }
+}
+
+/// The first `penalty` milliseconds of holding only charge the boat and add no speed.
+pub struct ChargeUp {
+    pub penalty: u64,
+}
+
+impl BoatModel for ChargeUp {
+    fn distance(&self, hold: u64, time: u64) -> u128 {
+        hold.saturating_sub(self.penalty) as u128 * time.saturating_sub(hold) as u128
+    }
+
+    // The distance is zero for all hold times up to the penalty, which breaks the ternary search.
+    fn best_hold(&self, time: u64) -> u64 {
+        time.midpoint(self.penalty).min(time)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn brute_force(model: &impl BoatModel, time: u64, record: u64) -> Option<(u64, u64)> {
+        let wins: Vec<u64> = (0..=time)
+            .filter(|hold| model.distance(*hold, time) > record as u128)
+            .collect();
+        wins.first().map(|first| (*first, *wins.last().unwrap()))
+    }
+
+    #[test]
+    fn models_match_brute_force() {
+        for time in 0..30 {
+            for record in (0..300).step_by(7) {
+                assert_eq!(
+                    Linear.winning_interval(time, record),
+                    brute_force(&Linear, time, record)
+                );
+                let model = Accelerating { exponent: 2 };
+                assert_eq!(
+                    model.winning_interval(time, record),
+                    brute_force(&model, time, record)
+                );
+                let model = SpeedCap { max_speed: 6 };
+                assert_eq!(
+                    model.winning_interval(time, record),
+                    brute_force(&model, time, record)
+                );
+                let model = ChargeUp { penalty: 4 };
+                assert_eq!(
+                    model.winning_interval(time, record),
+                    brute_force(&model, time, record)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_count_wins() {
+        assert_eq!(Linear.count_wins(30, 200), 9);
+        assert_eq!(SpeedCap { max_speed: 15 }.count_wins(30, 200), 6);
+        assert_eq!(SpeedCap { max_speed: 10 }.count_wins(30, 200), 0);
+        assert_eq!(ChargeUp { penalty: 2 }.count_wins(7, 1), 4);
+    }
+
+    #[test]
+    fn test_large_times() {
+        let model = Accelerating { exponent: 3 };
+        assert_eq!(model.count_wins(10_000_000_000, 5), 9_999_999_999);
+        assert_eq!(model.count_wins(u64::MAX, 5), u64::MAX - 1);
+        assert_eq!(
+            SpeedCap { max_speed: 6 }.count_wins(u64::MAX, 0),
+            u64::MAX - 1
+        );
+        assert_eq!(ChargeUp { penalty: u64::MAX }.count_wins(u64::MAX, 0), 0);
+    }
+}
@@ -1,3 +1,7 @@
+pub mod boat;
+
+use boat::BoatModel;
+use num::{BigUint, One, ToPrimitive, Zero};
 use std::str::FromStr;
 
 struct Races {
@@ -32,6 +36,60 @@ impl FromStr for Races {
     }
 }
 
+/// All hold times from `first` to `last` (inclusive) beat the record.
+#[derive(Debug, PartialEq, Eq)]
+pub struct WinningInterval {
+    pub first: BigUint,
+    pub last: BigUint,
+}
+
+impl WinningInterval {
+    pub fn len(&self) -> BigUint {
+        &self.last - &self.first + BigUint::one()
+    }
+}
+
+fn beats(time: &BigUint, record: &BigUint, hold: &BigUint) -> bool {
+    hold <= time && hold * (time - hold) > *record
+}
+
+/// Solves `hold * (time - hold) > record` exactly.
+/// The winning hold times lie strictly between the roots `(time +- sqrt(time^2 - 4 * record)) / 2`.
+/// With `s = isqrt(time^2 - 4 * record)` the lower root lies in `((time - s - 1) / 2, (time - s) / 2]`,
+/// so the first winning hold time is at most two steps above `(time - s) / 2`.
+pub fn winning_interval(time: &BigUint, record: &BigUint) -> Option<WinningInterval> {
+    let middle = time / 2u32;
+    if !beats(time, record, &middle) {
+        return None;
+    }
+    // The middle wins, so time^2 > 4 * record.
+    let discriminant = time * time - record * 4u32;
+    let mut first = (time - discriminant.sqrt()) / 2u32;
+    while !beats(time, record, &first) {
+        first += 1u32;
+    }
+    let last = time - &first;
+    Some(WinningInterval { first, last })
+}
+
+pub fn count_wins(time: &BigUint, record: &BigUint) -> BigUint {
+    winning_interval(time, record)
+        .map(|interval| interval.len())
+        .unwrap_or_else(BigUint::zero)
+}
+
+/// Reads all numbers of a line as one number by ignoring the spaces.
+fn parse_concatenated(line: &str) -> BigUint {
+    line.split_once(':')
+        .unwrap()
+        .1
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>()
+        .parse::<BigUint>()
+        .unwrap()
+}
+
 pub fn process_part1(input: &str) -> u64 {
     let races = Races::from_str(input).unwrap();
     races
@@ -47,26 +105,22 @@ pub fn process_part1(input: &str) -> u64 {
         .product::<usize>() as u64
 }
 
-pub fn process_part2(input: &str) -> u64 {
+/// Like `process_part1`, but the boats move according to `model`.
+pub fn process_with_model(input: &str, model: &impl BoatModel) -> u64 {
     let races = Races::from_str(input).unwrap();
-    let time = races
+    races
         .times
         .iter()
-        .map(|t| t.to_string())
-        .collect::<String>()
-        .parse::<u64>()
-        .unwrap();
-    let distance = races
-        .distances
-        .iter()
-        .map(|t| t.to_string())
-        .collect::<String>()
-        .parse::<u64>()
-        .unwrap();
-    (0..=time)
-        .map(|hold_time| (time - hold_time) * hold_time)
-        .filter(|d| d > &distance)
-        .count() as u64
+        .zip(races.distances.iter())
+        .map(|(t, min_d)| model.count_wins(*t, *min_d))
+        .product()
+}
+
+pub fn process_part2(input: &str) -> u64 {
+    let mut lines = input.lines();
+    let time = parse_concatenated(lines.next().unwrap());
+    let distance = parse_concatenated(lines.next().unwrap());
+    count_wins(&time, &distance).to_u64().unwrap()
 }
 
 #[cfg(test)]
@@ -86,4 +140,48 @@ Distance:  9  40  200";
 Distance:  9  40  200";
         assert_eq!(71503_u64, process_part2(input));
     }
+
+    #[test]
+    fn test_process_with_model() {
+        let input = "Time:      7  15   30
+Distance:  9  40  200";
+        assert_eq!(288_u64, process_with_model(input, &boat::Linear));
+        assert_eq!(
+            0_u64,
+            process_with_model(input, &boat::SpeedCap { max_speed: 4 })
+        );
+    }
+
+    #[test]
+    fn closed_form_matches_brute_force() {
+        for time in 0_u64..40 {
+            for record in 0..=(time * time / 4 + 1) {
+                let wins: Vec<u64> = (0..=time).filter(|h| h * (time - h) > record).collect();
+                let interval = winning_interval(&time.into(), &record.into());
+                match (wins.first(), wins.last()) {
+                    (Some(first), Some(last)) => assert_eq!(
+                        interval,
+                        Some(WinningInterval {
+                            first: (*first).into(),
+                            last: (*last).into()
+                        })
+                    ),
+                    _ => assert_eq!(interval, None),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn beyond_u64() {
+        // time = 2 * 10^30, record = 10^60 - 10^30: wins for |hold - 10^30|^2 < 10^30.
+        let time: BigUint = "2000000000000000000000000000000".parse().unwrap();
+        let record: BigUint = "999999999999999999999999999999000000000000000000000000000000"
+            .parse()
+            .unwrap();
+        let interval = winning_interval(&time, &record).unwrap();
+        assert_eq!(interval.first.to_string(), "999999999999999000000000000001");
+        assert_eq!(interval.last.to_string(), "1000000000000000999999999999999");
+        assert_eq!(count_wins(&time, &record).to_string(), "1999999999999999");
+    }
 }
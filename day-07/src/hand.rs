@@ -0,0 +1,718 @@
+use std::cmp::Ordering;
+
+use itertools::Itertools;
+
+/// How hands of the same type are compared.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TieBreak {
+    /// Compare the cards in the order they were dealt.
+    Lexicographic,
+    /// Compare the cards of the largest groups first, like in poker.
+    SortedGroups,
+}
+
+/// Card ordering, wild ranks and tie breaking of a Camel Cards game.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rules {
+    /// Ranks from weakest to strongest.
+    order: Vec<char>,
+    wild: Vec<char>,
+    tie_break: TieBreak,
+    /// If not empty, every card is written as its rank followed by one of these suits.
+    suits: Vec<char>,
+    straights: bool,
+}
+
+impl Rules {
+    /// `order` lists the ranks from weakest to strongest and `wild` the ranks acting as jokers.
+    pub fn new(order: &str, wild: &str, tie_break: TieBreak) -> Result<Self, String> {
+        let order: Vec<char> = order.chars().collect();
+        if !order.iter().all_unique() {
+            return Err(format!("The card order {:?} contains duplicates", order));
+        }
+        let wild: Vec<char> = wild.chars().collect();
+        if let Some(c) = wild.iter().find(|c| !order.contains(c)) {
+            return Err(format!("The wild card {} is not in the card order", c));
+        }
+        Ok(Rules {
+            order,
+            wild,
+            tie_break,
+            suits: Vec::new(),
+            straights: false,
+        })
+    }
+
+    /// Cards carry one of the `suits` after their rank, which makes flushes possible.
+    pub fn with_suits(mut self, suits: &str) -> Self {
+        self.suits = suits.chars().collect();
+        self
+    }
+
+    /// Hands of consecutive ranks count as straights.
+    pub fn with_straights(mut self) -> Self {
+        self.straights = true;
+        self
+    }
+
+    /// Rules of part 1: `J` is a jack and no card is wild.
+    pub fn part1() -> Self {
+        Rules::new("23456789TJQKA", "", TieBreak::Lexicographic).unwrap()
+    }
+
+    /// Rules of part 2: `J` is a joker, which is wild and the weakest card.
+    pub fn part2() -> Self {
+        Rules::new("J23456789TQKA", "J", TieBreak::Lexicographic).unwrap()
+    }
+
+    fn strength(&self, card: &char) -> Option<usize> {
+        self.order.iter().position(|c| c == card)
+    }
+
+    fn is_wild(&self, card: &char) -> bool {
+        self.wild.contains(card)
+    }
+}
+
+/// Type of a hand of any length. Hands without a straight or flush are described by the sizes of
+/// their groups of equal ranks, largest first, e.g. `[3, 2]` for a full house. Larger groups
+/// win, so with five cards this gives the seven types of the puzzle.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum Type {
+    Groups(Vec<usize>),
+    /// All ranks are consecutive.
+    Straight,
+    /// All cards have the same suit.
+    Flush,
+    StraightFlush,
+}
+
+impl Type {
+    /// Places straights and flushes like poker does: above three of a kind and below a full
+    /// house, and straight flushes just below five of a kind.
+    fn key(&self) -> (u8, &[usize]) {
+        match self {
+            Type::Groups(groups) if groups.first().is_some_and(|g| *g >= 5) => (4, groups),
+            Type::Groups(groups) if groups.as_slice() >= [3, 2].as_slice() => (2, groups),
+            Type::Groups(groups) => (0, groups),
+            Type::Straight => (1, &[0]),
+            Type::Flush => (1, &[1]),
+            Type::StraightFlush => (3, &[]),
+        }
+    }
+}
+
+impl Ord for Type {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
+impl PartialOrd for Type {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl std::fmt::Display for Type {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Type::Groups(groups) => match groups.as_slice() {
+                [5] => write!(f, "five of a kind"),
+                [4, 1] => write!(f, "four of a kind"),
+                [3, 2] => write!(f, "full house"),
+                [3, 1, 1] => write!(f, "three of a kind"),
+                [2, 2, 1] => write!(f, "two pair"),
+                [2, 1, 1, 1] => write!(f, "one pair"),
+                [1, 1, 1, 1, 1] => write!(f, "high card"),
+                _ => write!(f, "groups {}", groups.iter().join("-")),
+            },
+            Type::Straight => write!(f, "straight"),
+            Type::Flush => write!(f, "flush"),
+            Type::StraightFlush => write!(f, "straight flush"),
+        }
+    }
+}
+
+/// A hand evaluated under some `Rules`. Stronger hands compare greater.
+/// Only hands parsed with the same rules should be compared.
+#[derive(Debug)]
+pub struct Hand {
+    cards: Vec<char>,
+    /// Suit of each card, empty if the rules have no suits.
+    suits: Vec<char>,
+    pub bid: u64,
+    hand_type: Type,
+    /// Wild cards replaced to reach `hand_type`.
+    substitutions: Vec<Substitution>,
+    /// Card strengths with their rank in tie breaking order.
+    key: Vec<(usize, char)>,
+}
+
+/// The concrete card a wild card at `position` stands for.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Substitution {
+    pub position: usize,
+    pub rank: char,
+    /// Only set if the rules have suits.
+    pub suit: Option<char>,
+}
+
+/// Why one hand ranks above, below or equal to another.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Comparison {
+    Type {
+        ordering: Ordering,
+        this: Type,
+        other: Type,
+    },
+    /// Same type, decided by the first differing card in tie breaking order. The positions
+    /// are the indices of the two cards in the hands as dealt.
+    Card {
+        ordering: Ordering,
+        hand_type: Type,
+        position: usize,
+        other_position: usize,
+        this: char,
+        other: char,
+    },
+    Equal,
+}
+
+impl Comparison {
+    pub fn ordering(&self) -> Ordering {
+        match self {
+            Comparison::Type { ordering, .. } | Comparison::Card { ordering, .. } => *ordering,
+            Comparison::Equal => Ordering::Equal,
+        }
+    }
+}
+
+impl std::fmt::Display for Comparison {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let verb = |ordering: &Ordering| match ordering {
+            Ordering::Greater => "beats",
+            _ => "loses to",
+        };
+        match self {
+            Comparison::Type {
+                ordering,
+                this,
+                other,
+            } => write!(f, "{} {} {}", this, verb(ordering), other),
+            Comparison::Card {
+                ordering,
+                hand_type,
+                position,
+                other_position,
+                this,
+                other,
+            } if position == other_position => write!(
+                f,
+                "both are {}, but at card {} {} {} {}",
+                hand_type,
+                position + 1,
+                this,
+                verb(ordering),
+                other
+            ),
+            Comparison::Card {
+                ordering,
+                hand_type,
+                position,
+                other_position,
+                this,
+                other,
+            } => write!(
+                f,
+                "both are {}, but {} (card {}) {} {} (card {})",
+                hand_type,
+                this,
+                position + 1,
+                verb(ordering),
+                other,
+                other_position + 1
+            ),
+            Comparison::Equal => write!(f, "both hands are equally strong"),
+        }
+    }
+}
+
+impl Hand {
+    pub fn parse(s: &str, rules: &Rules) -> Result<Self, String> {
+        let (cards_str, bid_str) = s
+            .split_once(' ')
+            .ok_or(format!("The line {} has no bid", s))?;
+        let (cards, suits) = split_suits(cards_str, rules)?;
+        let strengths = cards
+            .iter()
+            .map(|c| {
+                rules
+                    .strength(c)
+                    .map(|s| (s, *c))
+                    .ok_or(format!("The char {} should not be in the input", c))
+            })
+            .collect::<Result<Vec<(usize, char)>, String>>()?;
+        let bid: u64 = bid_str.parse().map_err(|e| format!("{:?}", e))?;
+        let key = match rules.tie_break {
+            TieBreak::Lexicographic => strengths,
+            TieBreak::SortedGroups => strengths
+                .iter()
+                .map(|s| (strengths.iter().filter(|o| o == &s).count(), *s))
+                .sorted()
+                .rev()
+                .map(|(_, s)| s)
+                .collect(),
+        };
+        let (hand_type, substitutions) = classify(&cards, &suits, rules);
+        Ok(Hand {
+            cards,
+            suits,
+            bid,
+            hand_type,
+            substitutions,
+            key,
+        })
+    }
+
+    pub fn cards(&self) -> &[char] {
+        &self.cards
+    }
+
+    pub fn suits(&self) -> &[char] {
+        &self.suits
+    }
+
+    pub fn get_type(&self) -> &Type {
+        &self.hand_type
+    }
+
+    /// How the wild cards are replaced to reach the type of the hand.
+    pub fn substitutions(&self) -> &[Substitution] {
+        &self.substitutions
+    }
+
+    /// Compares the hands like `cmp` and tells what decided it.
+    pub fn compare(&self, other: &Hand) -> Comparison {
+        if self.hand_type != other.hand_type {
+            return Comparison::Type {
+                ordering: self.hand_type.cmp(&other.hand_type),
+                this: self.hand_type.clone(),
+                other: other.hand_type.clone(),
+            };
+        }
+        self.key
+            .iter()
+            .zip(other.key.iter())
+            .enumerate()
+            .find(|(_, (a, b))| a != b)
+            .map(|(index, (a, b))| Comparison::Card {
+                ordering: a.cmp(b),
+                hand_type: self.hand_type.clone(),
+                position: self.dealt_position(index),
+                other_position: other.dealt_position(index),
+                this: a.1,
+                other: b.1,
+            })
+            .unwrap_or(Comparison::Equal)
+    }
+
+    /// Index in the dealt cards of the card at `index` in tie breaking order. Equal cards keep
+    /// their dealing order within the tie breaking order.
+    fn dealt_position(&self, index: usize) -> usize {
+        let card = self.key[index].1;
+        let earlier = self.key[..index].iter().filter(|k| k.1 == card).count();
+        self.cards
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| **c == card)
+            .nth(earlier)
+            .unwrap()
+            .0
+    }
+}
+
+/// Splits the cards into ranks and suits if the rules have suits.
+fn split_suits(cards_str: &str, rules: &Rules) -> Result<(Vec<char>, Vec<char>), String> {
+    let chars: Vec<char> = cards_str.chars().collect();
+    if rules.suits.is_empty() {
+        return Ok((chars, Vec::new()));
+    }
+    if !chars.len().is_multiple_of(2) {
+        return Err(format!("The card {} has no suit", chars[chars.len() - 1]));
+    }
+    if let Some(suit) = chars
+        .iter()
+        .skip(1)
+        .step_by(2)
+        .find(|s| !rules.suits.contains(s))
+    {
+        return Err(format!("The suit {} should not be in the input", suit));
+    }
+    Ok((
+        chars.iter().step_by(2).copied().collect(),
+        chars.iter().skip(1).step_by(2).copied().collect(),
+    ))
+}
+
+/// Sizes of the groups of equal cards, largest first. The wild cards join the largest group.
+fn group_sizes(cards: &[char], rules: &Rules) -> Vec<usize> {
+    let wild = cards.iter().filter(|c| rules.is_wild(c)).count();
+    let mut groups: Vec<usize> = cards
+        .iter()
+        .filter(|c| !rules.is_wild(c))
+        .counts()
+        .into_values()
+        .sorted()
+        .rev()
+        .collect();
+    match groups.first_mut() {
+        Some(largest) => *largest += wild,
+        None => groups.push(wild),
+    }
+    groups
+}
+
+/// Wild cards may take any rank and suit, so they fill gaps in straights and match any flush.
+fn is_straight(cards: &[char], rules: &Rules) -> bool {
+    let strengths: Vec<usize> = cards
+        .iter()
+        .filter(|c| !rules.is_wild(c))
+        .flat_map(|c| rules.strength(c))
+        .collect();
+    let span = match (strengths.iter().min(), strengths.iter().max()) {
+        (Some(min), Some(max)) => max - min + 1,
+        _ => 0,
+    };
+    cards.len() <= rules.order.len() && strengths.iter().all_unique() && span <= cards.len()
+}
+
+fn is_flush(cards: &[char], suits: &[char], rules: &Rules) -> bool {
+    !suits.is_empty()
+        && cards
+            .iter()
+            .zip(suits.iter())
+            .filter(|(c, _)| !rules.is_wild(c))
+            .map(|(_, s)| s)
+            .all_equal()
+}
+
+fn wild_positions<'a>(cards: &'a [char], rules: &'a Rules) -> impl Iterator<Item = usize> + 'a {
+    cards
+        .iter()
+        .enumerate()
+        .filter(|(_, c)| rules.is_wild(c))
+        .map(|(i, _)| i)
+}
+
+/// All wild cards become the rank of the largest group, or the strongest rank if all cards are wild.
+fn group_ranks(cards: &[char], rules: &Rules) -> Vec<char> {
+    let rank = cards
+        .iter()
+        .filter(|c| !rules.is_wild(c))
+        .counts()
+        .into_iter()
+        .max_by_key(|(c, count)| (*count, rules.strength(c)))
+        .map(|(c, _)| *c)
+        .or_else(|| {
+            rules
+                .order
+                .iter()
+                .rev()
+                .find(|c| !rules.is_wild(c))
+                .copied()
+        })
+        .unwrap_or(rules.order[rules.order.len() - 1]);
+    vec![rank; wild_positions(cards, rules).count()]
+}
+
+/// The wild cards fill the missing ranks of the highest run containing all other cards.
+fn straight_ranks(cards: &[char], rules: &Rules) -> Vec<char> {
+    let strengths: Vec<usize> = cards
+        .iter()
+        .filter(|c| !rules.is_wild(c))
+        .flat_map(|c| rules.strength(c))
+        .collect();
+    let highest_start = rules.order.len() - cards.len();
+    let start = strengths
+        .iter()
+        .min()
+        .map_or(highest_start, |min| (*min).min(highest_start));
+    (start..start + cards.len())
+        .filter(|s| !strengths.contains(s))
+        .map(|s| rules.order[s])
+        .collect()
+}
+
+fn flush_suit(cards: &[char], suits: &[char], rules: &Rules) -> char {
+    cards
+        .iter()
+        .zip(suits.iter())
+        .find(|(c, _)| !rules.is_wild(c))
+        .map_or(rules.suits[0], |(_, s)| *s)
+}
+
+fn classify(cards: &[char], suits: &[char], rules: &Rules) -> (Type, Vec<Substitution>) {
+    let straight = rules.straights && is_straight(cards, rules);
+    let flush = is_flush(cards, suits, rules);
+    let suit = flush.then(|| flush_suit(cards, suits, rules));
+    let substitute = |ranks: Vec<char>, suit: Option<char>| {
+        wild_positions(cards, rules)
+            .zip(ranks)
+            .map(|(position, rank)| Substitution {
+                position,
+                rank,
+                suit: suit.or_else(|| suits.get(position).copied()),
+            })
+            .collect::<Vec<Substitution>>()
+    };
+    let candidates = [
+        Some((
+            Type::Groups(group_sizes(cards, rules)),
+            substitute(group_ranks(cards, rules), None),
+        )),
+        (straight && flush).then(|| {
+            (
+                Type::StraightFlush,
+                substitute(straight_ranks(cards, rules), suit),
+            )
+        }),
+        straight.then(|| {
+            (
+                Type::Straight,
+                substitute(straight_ranks(cards, rules), None),
+            )
+        }),
+        flush.then(|| (Type::Flush, substitute(group_ranks(cards, rules), suit))),
+    ];
+    candidates
+        .into_iter()
+        .flatten()
+        .max_by(|(a, _), (b, _)| a.cmp(b))
+        .unwrap()
+}
+
+impl PartialEq for Hand {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Hand {}
+
+impl Ord for Hand {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.hand_type
+            .cmp(&other.hand_type)
+            .then_with(|| self.key.cmp(&other.key))
+    }
+}
+
+impl PartialOrd for Hand {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process_with_rules;
+
+    fn hand_type(cards: &str, rules: &Rules) -> Type {
+        Hand::parse(&format!("{} 1", cards), rules)
+            .unwrap()
+            .get_type()
+            .clone()
+    }
+
+    fn groups(groups: &[usize]) -> Type {
+        Type::Groups(groups.to_vec())
+    }
+
+    #[test]
+    fn test_card_ordering() {
+        let rules = Rules::part1();
+        assert!(rules.strength(&'A') > rules.strength(&'9'));
+        assert!(rules.strength(&'T') > rules.strength(&'9'));
+        let rules = Rules::part2();
+        assert!(rules.strength(&'J') < rules.strength(&'2'));
+    }
+
+    #[test]
+    fn test_type() {
+        let rules = Rules::part1();
+        assert_eq!(hand_type("JJAAA", &rules), groups(&[3, 2]));
+        assert_eq!(hand_type("23432", &rules), groups(&[2, 2, 1]));
+        let rules = Rules::part2();
+        assert_eq!(hand_type("JJAAA", &rules), groups(&[5]));
+        assert_eq!(hand_type("JKJAA", &rules), groups(&[4, 1]));
+        assert_eq!(hand_type("JJJJJ", &rules), groups(&[5]));
+        assert_eq!(hand_type("AAKKJ", &rules), groups(&[3, 2]));
+        assert_eq!(hand_type("AQKKJ", &rules), groups(&[3, 1, 1]));
+        assert_eq!(hand_type("AQKJJ", &rules), groups(&[3, 1, 1]));
+        assert_eq!(hand_type("AQKTJ", &rules), groups(&[2, 1, 1, 1]));
+    }
+
+    #[test]
+    fn test_type_ordering() {
+        let types = [
+            groups(&[1, 1, 1, 1, 1]),
+            groups(&[2, 1, 1, 1]),
+            groups(&[2, 2, 1]),
+            groups(&[3, 1, 1]),
+            Type::Straight,
+            Type::Flush,
+            groups(&[3, 2]),
+            groups(&[4, 1]),
+            Type::StraightFlush,
+            groups(&[5]),
+        ];
+        assert!(types.windows(2).all(|w| w[0] < w[1]));
+        assert!(groups(&[4, 3]) > groups(&[4, 2, 1]));
+        assert!(groups(&[3, 3, 1]) > groups(&[3, 2, 2]));
+        assert!(groups(&[2, 2, 2]) < Type::Straight);
+    }
+
+    #[test]
+    fn test_longer_hands() {
+        let rules = Rules::part2().with_straights();
+        assert_eq!(hand_type("AAAKKKQ", &rules), groups(&[3, 3, 1]));
+        assert_eq!(hand_type("AAJKKKQ", &rules), groups(&[4, 2, 1]));
+        assert_eq!(hand_type("23456J", &rules), Type::Straight);
+        assert_eq!(hand_type("2345J7", &rules), Type::Straight);
+        assert_eq!(hand_type("2345J8", &rules), groups(&[2, 1, 1, 1, 1]));
+        assert_eq!(
+            process_with_rules("AAAA22 1\nKKKKKK 2\n234567 3", &rules),
+            Ok(11)
+        );
+        assert!(process_with_rules("32T3K 765\nT55X5 684", &Rules::part1()).is_err());
+    }
+
+    #[test]
+    fn test_suits() {
+        let rules = Rules::part2().with_suits("shdc").with_straights();
+        assert_eq!(hand_type("2h3h4h5h6h7h", &rules), Type::StraightFlush);
+        assert_eq!(hand_type("2h3h4h5h6h7s", &rules), Type::Straight);
+        assert_eq!(hand_type("2h2hKh5h9h7h", &rules), Type::Flush);
+        assert_eq!(hand_type("2h2hKhJs9h7h", &rules), Type::Flush);
+        assert_eq!(hand_type("AhAsAdAcKhKs", &rules), groups(&[4, 2]));
+        assert!(Hand::parse("2h3x 1", &rules).is_err());
+        assert!(Hand::parse("2h3 1", &rules).is_err());
+    }
+
+    #[test]
+    fn test_tie_break() {
+        let rules = Rules::part1();
+        let threes = Hand::parse("33AKQ 1", &rules).unwrap();
+        let twos = Hand::parse("A2Q2K 1", &rules).unwrap();
+        assert!(threes < twos);
+        let rules = Rules::new("23456789TJQKA", "", TieBreak::SortedGroups).unwrap();
+        let threes = Hand::parse("33AKQ 1", &rules).unwrap();
+        let twos = Hand::parse("A2Q2K 1", &rules).unwrap();
+        assert!(threes > twos);
+    }
+
+    /// Replaces the wild cards and checks that the concrete hand has the same type.
+    fn check_substitutions(cards: &str, rules: &Rules) -> Vec<Substitution> {
+        let hand = Hand::parse(&format!("{} 1", cards), rules).unwrap();
+        let mut ranks = hand.cards().to_vec();
+        let mut suits = hand.suits().to_vec();
+        for sub in hand.substitutions() {
+            ranks[sub.position] = sub.rank;
+            if let Some(suit) = sub.suit {
+                suits[sub.position] = suit;
+            }
+        }
+        let concrete: String = if suits.is_empty() {
+            ranks.iter().collect()
+        } else {
+            ranks
+                .iter()
+                .zip(suits.iter())
+                .map(|(r, s)| format!("{}{}", r, s))
+                .collect()
+        };
+        let mut tame = rules.clone();
+        tame.wild.clear();
+        assert_eq!(
+            hand_type(&concrete, &tame),
+            *hand.get_type(),
+            "{}",
+            concrete
+        );
+        hand.substitutions().to_vec()
+    }
+
+    #[test]
+    fn test_substitutions() {
+        let rules = Rules::part2();
+        let sub = |position, rank| Substitution {
+            position,
+            rank,
+            suit: None,
+        };
+        assert_eq!(check_substitutions("T55J5", &rules), vec![sub(3, '5')]);
+        assert_eq!(
+            check_substitutions("KTJJT", &rules),
+            vec![sub(2, 'T'), sub(3, 'T')]
+        );
+        assert_eq!(check_substitutions("JJJJJ", &rules).len(), 5);
+        assert_eq!(check_substitutions("32T3K", &rules), vec![]);
+        let rules = Rules::part2().with_straights().with_suits("shdc");
+        for cards in ["2h3hJs5h6h", "JsJhTdKdQd", "2hJs9h7hKh", "AhJsAd7cKh"] {
+            check_substitutions(cards, &rules);
+        }
+        assert_eq!(
+            check_substitutions("JsJhTdKdQd", &rules),
+            vec![
+                Substitution {
+                    position: 0,
+                    rank: '9',
+                    suit: Some('d')
+                },
+                Substitution {
+                    position: 1,
+                    rank: 'A',
+                    suit: Some('d')
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn test_compare() {
+        let rules = Rules::part2();
+        let full_house = Hand::parse("22JKK 1", &rules).unwrap();
+        let four = Hand::parse("2JJ2K 1", &rules).unwrap();
+        let comparison = full_house.compare(&four);
+        assert_eq!(comparison.ordering(), Ordering::Less);
+        assert_eq!(comparison.to_string(), "full house loses to four of a kind");
+
+        let weaker_four = Hand::parse("2JJ2A 1", &rules).unwrap();
+        let comparison = four.compare(&weaker_four);
+        assert_eq!(comparison.ordering(), four.cmp(&weaker_four));
+        assert_eq!(
+            comparison.to_string(),
+            "both are four of a kind, but at card 5 K loses to A"
+        );
+        assert_eq!(four.compare(&four), Comparison::Equal);
+
+        let rules = Rules::new("23456789TJQKA", "", TieBreak::SortedGroups).unwrap();
+        let low_pairs = Hand::parse("K2K23 1", &rules).unwrap();
+        let high_pairs = Hand::parse("3K3KA 1", &rules).unwrap();
+        let comparison = low_pairs.compare(&high_pairs);
+        assert_eq!(comparison.ordering(), Ordering::Less);
+        assert_eq!(
+            comparison.to_string(),
+            "both are two pair, but 2 (card 2) loses to 3 (card 1)"
+        );
+    }
+
+    #[test]
+    fn test_invalid_rules() {
+        assert!(Rules::new("23A3", "", TieBreak::Lexicographic).is_err());
+        assert!(Rules::new("23A", "J", TieBreak::Lexicographic).is_err());
+        assert!(Hand::parse("2345X 1", &Rules::part1()).is_err());
+    }
+}
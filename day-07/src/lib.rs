@@ -1,29 +1,28 @@
-pub mod part1hand;
-mod part2hand;
+pub mod hand;
 
+use hand::{Hand, Rules};
 use itertools::Itertools;
-use std::str::FromStr;
 
-pub fn process_part1(input: &str) -> u64 {
-    input
+/// Total winnings of all hands ranked under `rules`, or the first line that is not a hand.
+pub fn process_with_rules(input: &str, rules: &Rules) -> Result<u64, String> {
+    let hands = input
         .lines()
-        .flat_map(part1hand::Hand::from_str)
+        .map(|line| Hand::parse(line, rules))
+        .collect::<Result<Vec<Hand>, String>>()?;
+    Ok(hands
+        .into_iter()
         .sorted()
-        .rev()
         .enumerate()
         .map(|(rank, hand)| (rank as u64 + 1) * hand.bid)
-        .sum()
+        .sum())
+}
+
+pub fn process_part1(input: &str) -> u64 {
+    process_with_rules(input, &Rules::part1()).unwrap()
 }
 
 pub fn process_part2(input: &str) -> u64 {
-    input
-        .lines()
-        .flat_map(part2hand::Hand::from_str)
-        .sorted()
-        .rev()
-        .enumerate()
-        .map(|(rank, hand)| (rank as u64 + 1) * hand.bid)
-        .sum()
+    process_with_rules(input, &Rules::part2()).unwrap()
 }
 
 #[cfg(test)]
@@ -0,0 +1,191 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+
+use crate::{Graph, Node};
+
+/// Sanity checks of a network before walking it. The checks ignore the instructions, so a start
+/// node reported as stuck can not reach an end node with any instructions.
+impl Graph {
+    fn successors<'a>(&'a self, node: &'a Node) -> impl Iterator<Item = &'a str> + 'a {
+        [node.left.as_str(), node.right.as_str()]
+            .into_iter()
+            .filter(|name| self.nodes.contains_key(*name))
+    }
+
+    fn sorted_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.nodes.keys().map(|n| n.as_str()).collect();
+        names.sort();
+        names
+    }
+
+    fn reachable<'a>(
+        edges: &HashMap<&'a str, Vec<&'a str>>,
+        from: impl Iterator<Item = &'a str>,
+    ) -> HashSet<&'a str> {
+        let mut stack: Vec<&str> = from.collect();
+        let mut seen: HashSet<&str> = stack.iter().copied().collect();
+        while let Some(name) = stack.pop() {
+            for next in edges.get(name).into_iter().flatten() {
+                if seen.insert(next) {
+                    stack.push(next);
+                }
+            }
+        }
+        seen
+    }
+
+    fn forward_edges(&self) -> HashMap<&str, Vec<&str>> {
+        self.nodes
+            .values()
+            .map(|node| (node.name.as_str(), self.successors(node).collect()))
+            .collect()
+    }
+
+    fn backward_edges(&self) -> HashMap<&str, Vec<&str>> {
+        let mut edges: HashMap<&str, Vec<&str>> = HashMap::new();
+        for node in self.nodes.values() {
+            for next in self.successors(node) {
+                edges.entry(next).or_default().push(&node.name);
+            }
+        }
+        edges
+    }
+
+    /// Nodes no start node (name ending with A) can ever reach, sorted by name.
+    pub fn unreachable_nodes(&self) -> Vec<&str> {
+        let starts = self.sorted_names().into_iter().filter(|n| n.ends_with('A'));
+        let reachable = Self::reachable(&self.forward_edges(), starts);
+        self.sorted_names()
+            .into_iter()
+            .filter(|n| !reachable.contains(n))
+            .collect()
+    }
+
+    /// Start nodes from which no end node (name ending with Z) can be reached, sorted by name.
+    pub fn stuck_starts(&self) -> Vec<&str> {
+        let ends = self.sorted_names().into_iter().filter(|n| n.ends_with('Z'));
+        let can_finish = Self::reachable(&self.backward_edges(), ends);
+        self.sorted_names()
+            .into_iter()
+            .filter(|n| n.ends_with('A') && !can_finish.contains(n))
+            .collect()
+    }
+
+    /// Strongly connected components found with Kosaraju's algorithm. Each component is sorted
+    /// by name and the components are sorted by their first name.
+    pub fn strongly_connected_components(&self) -> Vec<Vec<&str>> {
+        let forward = self.forward_edges();
+        let mut finished: Vec<&str> = Vec::new();
+        let mut visited: HashSet<&str> = HashSet::new();
+        for root in self.sorted_names() {
+            if !visited.insert(root) {
+                continue;
+            }
+            let mut stack: Vec<(&str, usize)> = vec![(root, 0)];
+            while let Some((name, child)) = stack.pop() {
+                match forward[name].get(child) {
+                    Some(next) => {
+                        stack.push((name, child + 1));
+                        if visited.insert(next) {
+                            stack.push((next, 0));
+                        }
+                    }
+                    None => finished.push(name),
+                }
+            }
+        }
+
+        let backward = self.backward_edges();
+        let mut assigned: HashSet<&str> = HashSet::new();
+        let mut components: Vec<Vec<&str>> = Vec::new();
+        for root in finished.into_iter().rev() {
+            if !assigned.insert(root) {
+                continue;
+            }
+            let mut component = vec![root];
+            let mut stack = vec![root];
+            while let Some(name) = stack.pop() {
+                for prev in backward.get(name).into_iter().flatten() {
+                    if assigned.insert(prev) {
+                        component.push(prev);
+                        stack.push(prev);
+                    }
+                }
+            }
+            component.sort();
+            components.push(component);
+        }
+        components.sort();
+        components
+    }
+
+    /// Graphviz representation with one edge per direction, labeled L or R.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph network {\n");
+        for name in self.sorted_names() {
+            let node = &self.nodes[name];
+            if node.left == node.right {
+                writeln!(
+                    dot,
+                    "    \"{}\" -> \"{}\" [label=\"L,R\"];",
+                    name, node.left
+                )
+                .unwrap();
+            } else {
+                writeln!(dot, "    \"{}\" -> \"{}\" [label=\"L\"];", name, node.left).unwrap();
+                writeln!(dot, "    \"{}\" -> \"{}\" [label=\"R\"];", name, node.right).unwrap();
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NETWORK: &str = "11A = (11B, XXX)
+11B = (XXX, 11Z)
+11Z = (11B, XXX)
+22A = (22B, 22B)
+22B = (22A, 22A)
+33B = (11A, 11A)
+XXX = (XXX, XXX)";
+
+    #[test]
+    fn test_reachability() {
+        let g = Graph::from_lines(NETWORK.lines());
+        assert_eq!(g.unreachable_nodes(), vec!["33B"]);
+        assert_eq!(g.stuck_starts(), vec!["22A"]);
+    }
+
+    #[test]
+    fn test_strongly_connected_components() {
+        let g = Graph::from_lines(NETWORK.lines());
+        assert_eq!(
+            g.strongly_connected_components(),
+            vec![
+                vec!["11A"],
+                vec!["11B", "11Z"],
+                vec!["22A", "22B"],
+                vec!["33B"],
+                vec!["XXX"]
+            ]
+        );
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let g = Graph::from_lines("AAA = (BBB, ZZZ)\nBBB = (AAA, AAA)".lines());
+        assert_eq!(
+            g.to_dot(),
+            "digraph network {
+    \"AAA\" -> \"BBB\" [label=\"L\"];
+    \"AAA\" -> \"ZZZ\" [label=\"R\"];
+    \"BBB\" -> \"AAA\" [label=\"L,R\"];
+}
+"
+        );
+    }
+}
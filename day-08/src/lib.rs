@@ -1,7 +1,11 @@
+mod analysis;
+pub mod network;
+
+use network::Network;
 use num::Integer;
-use std::{collections::HashMap, ops::ControlFlow, str::Lines};
+use std::{collections::HashMap, str::Lines};
 
-struct Graph {
+pub struct Graph {
     nodes: HashMap<String, Node>,
 }
 
@@ -13,7 +17,7 @@ struct Node {
 }
 
 impl Graph {
-    fn from_lines(lines: Lines) -> Self {
+    pub fn from_lines(lines: Lines) -> Self {
         let mut nodes: HashMap<String, Node> = HashMap::new();
         lines.for_each(|l| {
             let (name, tuple) = l.split_once(" = ").unwrap();
@@ -29,63 +33,107 @@ impl Graph {
         });
         Graph { nodes }
     }
+}
 
-    fn find_min_cycle_len(&self, node: &Node, instructions: &str) -> u64 {
-        let mut i: u64 = 0;
-        instructions.chars().cycle().try_fold(node, |acc, c| {
-            i += 1;
-            let new_node = match c {
-                'L' => self.nodes.get(&acc.left).unwrap(),
-                'R' => self.nodes.get(&acc.right).unwrap(),
-                _ => unimplemented!("Not possible."),
-            };
-            if new_node.name.ends_with('Z') {
-                ControlFlow::Break(())
-            } else {
-                ControlFlow::Continue(new_node)
-            }
-        });
-        i
+/// The walk of one ghost. After `offset` steps it repeats every `length` steps.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Cycle {
+    pub offset: u64,
+    pub length: u64,
+    /// Sorted steps before `offset + length` at which the ghost stands on an end node.
+    pub hits: Vec<u64>,
+}
+
+impl Cycle {
+    pub fn is_hit(&self, step: u64) -> bool {
+        let step = if step < self.offset {
+            step
+        } else {
+            self.offset + (step - self.offset) % self.length
+        };
+        self.hits.binary_search(&step).is_ok()
     }
 }
 
-pub fn process_part1(input: &str) -> u64 {
+/// Cycles of all ghosts starting on a node ending with A.
+pub fn ghost_cycles(input: &str) -> Vec<Cycle> {
     let mut lines = input.lines();
     let instructions = lines.next().unwrap();
     lines.next();
-    let g = Graph::from_lines(lines);
-
-    let mut i: u64 = 0;
-    let mut position: &Node = g.nodes.get(&"AAA".to_string()).unwrap();
-    let end_node: &Node = g.nodes.get(&"ZZZ".to_string()).unwrap();
-    while position != end_node {
-        instructions.chars().for_each(|c| {
-            i += 1;
-            position = match c {
-                'L' => g.nodes.get(&position.left).unwrap(),
-                'R' => g.nodes.get(&position.right).unwrap(),
-                _ => unimplemented!("Not possible."),
-            }
-        });
+    let network = Network::compile(&Graph::from_lines(lines), instructions).unwrap();
+    network
+        .nodes_ending_with('A')
+        .into_iter()
+        .map(|start| network.find_cycle(start))
+        .collect()
+}
+
+/// Solves `x = a1 mod m1` and `x = a2 mod m2` for moduli which need not be coprime.
+fn crt((a1, m1): (i128, i128), (a2, m2): (i128, i128)) -> Option<(i128, i128)> {
+    let gcd = m1.extended_gcd(&m2);
+    if (a2 - a1) % gcd.gcd != 0 {
+        return None;
     }
-    i
+    let lcm = m1 / gcd.gcd * m2;
+    let k = ((a2 - a1) / gcd.gcd * gcd.x).rem_euclid(m2 / gcd.gcd);
+    Some(((a1 + m1 * k).rem_euclid(lcm), lcm))
 }
 
-pub fn process_part2(input: &str) -> u64 {
+/// First step at which all ghosts stand on an end node, or `None` if that never happens.
+pub fn synchronise(cycles: &[Cycle]) -> Option<u64> {
+    let Some(last_entry) = cycles.iter().max_by_key(|c| c.offset) else {
+        return Some(0);
+    };
+    // Before every ghost is inside its cycle, the ghost entering last has only finitely many hits.
+    if let Some(step) = last_entry
+        .hits
+        .iter()
+        .take_while(|step| **step < last_entry.offset)
+        .find(|step| cycles.iter().all(|c| c.is_hit(**step)))
+    {
+        return Some(*step);
+    }
+    let mut residues: Vec<(i128, i128)> = vec![(0, 1)];
+    for cycle in cycles {
+        residues = residues
+            .iter()
+            .flat_map(|r| {
+                cycle
+                    .hits
+                    .iter()
+                    .filter(|hit| **hit >= cycle.offset)
+                    .flat_map(|hit| crt(*r, (*hit as i128, cycle.length as i128)))
+            })
+            .collect();
+        residues.sort();
+        residues.dedup();
+    }
+    let start = last_entry.offset as i128;
+    residues
+        .iter()
+        .map(|(r, m)| {
+            if *r >= start {
+                *r
+            } else {
+                r + Integer::div_ceil(&(start - r), m) * m
+            }
+        })
+        .min()
+        .map(|step| step as u64)
+}
+
+pub fn process_part1(input: &str) -> u64 {
     let mut lines = input.lines();
     let instructions = lines.next().unwrap();
     lines.next();
-    let g = Graph::from_lines(lines);
+    let network = Network::compile(&Graph::from_lines(lines), instructions).unwrap();
+    let start = network.index_of("AAA").unwrap();
+    let end = network.index_of("ZZZ").unwrap();
+    network.steps_between(start, end, u64::MAX).unwrap()
+}
 
-    // Every start node emits a path to a node ending with Z. This defines a cycle.
-    // Due to the hiden design of the input set, it is sufficient to just find a common point where
-    // all cycles converge. This is excatly the lowest common multiple of all cycles and the length
-    // of the instruction set.
-    g.nodes
-        .iter()
-        .filter(|(name, _)| name.ends_with('A'))
-        .map(|(_, node)| g.find_min_cycle_len(node, instructions))
-        .fold(instructions.len() as u64, |acc, l| acc.lcm(&l))
+pub fn process_part2(input: &str) -> u64 {
+    synchronise(&ghost_cycles(input)).expect("The ghosts never stand on end nodes together.")
 }
 
 #[cfg(test)]
@@ -130,4 +178,61 @@ ZZZ = (ZZZ, ZZZ)";
 XXX = (XXX, XXX)";
         assert_eq!(6_u64, process_part2(input));
     }
+
+    #[test]
+    fn test_process_part2_offset_hits() {
+        // 11A reaches its end node after 1 step and then every 3 steps,
+        // 22A after 2 steps and then every 2 steps.
+        let input = "L
+
+11A = (11Z, 11Z)
+11Z = (11B, 11B)
+11B = (11C, 11C)
+11C = (11Z, 11Z)
+22A = (22B, 22B)
+22B = (22Z, 22Z)
+22Z = (22C, 22C)
+22C = (22Z, 22Z)";
+        assert_eq!(4_u64, process_part2(input));
+    }
+
+    #[test]
+    fn test_find_cycle() {
+        let input = "LR
+
+11A = (11B, XXX)
+11B = (XXX, 11Z)
+11Z = (11B, XXX)
+XXX = (XXX, XXX)";
+        assert_eq!(
+            ghost_cycles(input),
+            vec![Cycle {
+                offset: 1,
+                length: 2,
+                hits: vec![2]
+            }]
+        );
+    }
+
+    #[test]
+    fn test_synchronise() {
+        let cycle = |offset, length, hits: &[u64]| Cycle {
+            offset,
+            length,
+            hits: hits.to_vec(),
+        };
+        assert_eq!(
+            synchronise(&[cycle(0, 4, &[1]), cycle(0, 6, &[3])]),
+            Some(9)
+        );
+        assert_eq!(synchronise(&[cycle(0, 4, &[0]), cycle(0, 2, &[1])]), None);
+        assert_eq!(
+            synchronise(&[cycle(5, 2, &[2, 5]), cycle(0, 1, &[0])]),
+            Some(2)
+        );
+        assert_eq!(
+            synchronise(&[cycle(5, 2, &[2, 6]), cycle(0, 5, &[0, 4])]),
+            Some(10)
+        );
+    }
 }
@@ -0,0 +1,201 @@
+use crate::{Cycle, Graph};
+
+/// A `Graph` compiled for walking. Nodes are numbered by their sorted names and the
+/// instructions are stored as bits, set for R.
+pub struct Network {
+    names: Vec<String>,
+    /// Left and right successor of every node.
+    next: Vec<[u32; 2]>,
+    is_end: Vec<bool>,
+    instructions: Vec<u64>,
+    instruction_len: usize,
+}
+
+impl Network {
+    /// Fails on empty instructions, on instructions other than `L` and `R`, and on edges to
+    /// undefined nodes.
+    pub fn compile(g: &Graph, instructions: &str) -> Result<Self, String> {
+        let mut names: Vec<String> = g.nodes.keys().cloned().collect();
+        names.sort();
+        let index = |name: &String| -> Result<u32, String> {
+            names
+                .binary_search(name)
+                .map(|i| i as u32)
+                .map_err(|_| format!("The node {} is not defined.", name))
+        };
+        let next = names
+            .iter()
+            .map(|name| {
+                let node = &g.nodes[name];
+                Ok([index(&node.left)?, index(&node.right)?])
+            })
+            .collect::<Result<_, String>>()?;
+        let is_end = names.iter().map(|name| name.ends_with('Z')).collect();
+        let instruction_len = instructions.chars().count();
+        if instruction_len == 0 {
+            return Err(String::from("There are no instructions."));
+        }
+        let mut bits = vec![0_u64; instruction_len.div_ceil(64)];
+        for (i, c) in instructions.chars().enumerate() {
+            match c {
+                'L' => (),
+                'R' => bits[i / 64] |= 1 << (i % 64),
+                _ => return Err(format!("The instruction {:?} is not L or R.", c)),
+            }
+        }
+        Ok(Network {
+            names,
+            next,
+            is_end,
+            instructions: bits,
+            instruction_len,
+        })
+    }
+
+    pub fn index_of(&self, name: &str) -> Option<u32> {
+        self.names
+            .binary_search_by(|n| n.as_str().cmp(name))
+            .ok()
+            .map(|i| i as u32)
+    }
+
+    /// Indices of all nodes whose name ends with `suffix`.
+    pub fn nodes_ending_with(&self, suffix: char) -> Vec<u32> {
+        (0..self.names.len() as u32)
+            .filter(|i| self.names[*i as usize].ends_with(suffix))
+            .collect()
+    }
+
+    #[inline]
+    fn direction(&self, instruction: usize) -> usize {
+        (self.instructions[instruction / 64] >> (instruction % 64)) as usize & 1
+    }
+
+    #[inline]
+    fn step(&self, node: u32, instruction: usize) -> u32 {
+        self.next[node as usize][self.direction(instruction)]
+    }
+
+    /// Number of steps from `start` to `end`, or `None` if `end` is not reached within `max_steps`.
+    pub fn steps_between(&self, start: u32, end: u32, max_steps: u64) -> Option<u64> {
+        let mut node = start;
+        let mut instruction = 0;
+        for step in 0..max_steps {
+            if node == end {
+                return Some(step);
+            }
+            node = self.step(node, instruction);
+            instruction += 1;
+            if instruction == self.instruction_len {
+                instruction = 0;
+            }
+        }
+        (node == end).then_some(max_steps)
+    }
+
+    /// Walks from `start` until a (node, instruction index) state repeats.
+    pub fn find_cycle(&self, start: u32) -> Cycle {
+        // First step each state was seen at, indexed by node * instruction_len + instruction.
+        let mut seen: Vec<u64> = vec![u64::MAX; self.names.len() * self.instruction_len];
+        let mut hits: Vec<u64> = Vec::new();
+        let mut node = start;
+        let mut instruction = 0;
+        let mut step: u64 = 0;
+        loop {
+            let state = node as usize * self.instruction_len + instruction;
+            if seen[state] != u64::MAX {
+                return Cycle {
+                    offset: seen[state],
+                    length: step - seen[state],
+                    hits,
+                };
+            }
+            seen[state] = step;
+            if self.is_end[node as usize] {
+                hits.push(step);
+            }
+            node = self.step(node, instruction);
+            instruction += 1;
+            if instruction == self.instruction_len {
+                instruction = 0;
+            }
+            step += 1;
+        }
+    }
+
+    /// Moves all ghosts step by step until they all stand on end nodes. Gives up after `max_steps`.
+    pub fn brute_force_ghosts(&self, starts: &[u32], max_steps: u64) -> Option<u64> {
+        let mut nodes = starts.to_vec();
+        let mut instruction = 0;
+        for step in 0..=max_steps {
+            if nodes.iter().all(|n| self.is_end[*n as usize]) {
+                return Some(step);
+            }
+            let direction = self.direction(instruction);
+            nodes
+                .iter_mut()
+                .for_each(|n| *n = self.next[*n as usize][direction]);
+            instruction += 1;
+            if instruction == self.instruction_len {
+                instruction = 0;
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "LR
+
+11A = (11B, XXX)
+11B = (XXX, 11Z)
+11Z = (11B, XXX)
+22A = (22B, XXX)
+22B = (22C, 22C)
+22C = (22Z, 22Z)
+22Z = (22B, 22B)
+XXX = (XXX, XXX)";
+
+    fn network() -> Network {
+        let mut lines = INPUT.lines();
+        let instructions = lines.next().unwrap();
+        lines.next();
+        Network::compile(&Graph::from_lines(lines), instructions).unwrap()
+    }
+
+    #[test]
+    fn test_invalid_instructions() {
+        let graph = Graph::from_lines("AAA = (AAA, AAA)".lines());
+        assert!(Network::compile(&graph, "LR").is_ok());
+        assert!(Network::compile(&graph, "LR\r").is_err());
+        assert!(Network::compile(&graph, "").is_err());
+        assert!(Network::compile(&graph, "LRé").is_err());
+        let graph = Graph::from_lines("AAA = (AAA, BBB)".lines());
+        assert!(Network::compile(&graph, "LR").is_err());
+    }
+
+    #[test]
+    fn test_brute_force_ghosts() {
+        let network = network();
+        let starts = network.nodes_ending_with('A');
+        assert_eq!(network.brute_force_ghosts(&starts, 100), Some(6));
+        assert_eq!(network.brute_force_ghosts(&starts, 5), None);
+    }
+
+    #[test]
+    fn test_steps_between() {
+        let network = network();
+        let start = network.index_of("11A").unwrap();
+        assert_eq!(
+            network.steps_between(start, network.index_of("11Z").unwrap(), 10),
+            Some(2)
+        );
+        assert_eq!(
+            network.steps_between(start, network.index_of("22Z").unwrap(), 10),
+            None
+        );
+    }
+}
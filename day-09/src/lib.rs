@@ -1,47 +1,103 @@
-fn parse_str(s: &str) -> Vec<Vec<i64>> {
-    s.lines()
-        .map(|l| l.split(' ').map(|n| n.parse::<i64>().unwrap()).collect())
-        .collect()
+pub mod sequence;
+
+use num::{BigInt, ToPrimitive, Zero};
+
+/// Extrapolated value of one line.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Extrapolation {
+    Exact(i64),
+    /// The line overflowed `i64` and was computed again with big integers.
+    Overflowed(BigInt),
+}
+
+impl Extrapolation {
+    pub fn value(&self) -> BigInt {
+        match self {
+            Extrapolation::Exact(n) => BigInt::from(*n),
+            Extrapolation::Overflowed(n) => n.clone(),
+        }
+    }
+}
+
+fn difference(nums: &[i64]) -> Option<Vec<i64>> {
+    nums.windows(2).map(|w| w[1].checked_sub(w[0])).collect()
 }
 
-fn difference(nums: &[i64]) -> Vec<i64> {
-    nums.iter()
-        .as_slice()
-        .windows(2)
-        .map(|w| w.get(1).unwrap() - w.first().unwrap())
+/// Extrapolates with `i64`, or `None` if any step overflows.
+fn extrapolate_checked(nums: &[i64], backwards: bool) -> Option<i64> {
+    let mut stack: Vec<i64> = Vec::new();
+    let mut current: Vec<i64> = nums.to_vec();
+    while current.iter().any(|n| *n != 0) {
+        stack.push(if backwards {
+            *current.first().unwrap()
+        } else {
+            *current.last().unwrap()
+        });
+        current = difference(&current)?;
+    }
+    if backwards {
+        stack
+            .iter()
+            .rev()
+            .try_fold(0_i64, |acc, n| n.checked_sub(acc))
+    } else {
+        stack.iter().try_fold(0_i64, |acc, n| acc.checked_add(*n))
+    }
+}
+
+fn extrapolate_big(nums: &[BigInt], backwards: bool) -> BigInt {
+    let mut stack: Vec<BigInt> = Vec::new();
+    let mut current: Vec<BigInt> = nums.to_vec();
+    while current.iter().any(|n| !n.is_zero()) {
+        stack.push(if backwards {
+            current.first().unwrap().clone()
+        } else {
+            current.last().unwrap().clone()
+        });
+        current = current.windows(2).map(|w| &w[1] - &w[0]).collect();
+    }
+    if backwards {
+        stack.iter().rev().fold(BigInt::zero(), |acc, n| n - acc)
+    } else {
+        stack.iter().sum()
+    }
+}
+
+/// Extrapolates one line forwards or backwards, falling back to big integers on overflow.
+pub fn extrapolate_line(line: &str, backwards: bool) -> Extrapolation {
+    let nums: Result<Vec<i64>, _> = line.split(' ').map(|n| n.parse::<i64>()).collect();
+    if let Some(n) = nums
+        .ok()
+        .and_then(|nums| extrapolate_checked(&nums, backwards))
+    {
+        return Extrapolation::Exact(n);
+    }
+    let nums: Vec<BigInt> = line.split(' ').map(|n| n.parse().unwrap()).collect();
+    Extrapolation::Overflowed(extrapolate_big(&nums, backwards))
+}
+
+pub fn extrapolate_lines(input: &str, backwards: bool) -> Vec<Extrapolation> {
+    input
+        .lines()
+        .map(|l| extrapolate_line(l, backwards))
         .collect()
 }
 
-pub fn process_part1(input: &str) -> i64 {
-    let lines = parse_str(input);
-    lines
+fn sum(input: &str, backwards: bool) -> i64 {
+    extrapolate_lines(input, backwards)
         .iter()
-        .map(|l| {
-            let mut stack: Vec<i64> = Vec::new();
-            let mut current: Vec<i64> = l.to_vec();
-            while current.iter().any(|n| *n != 0) {
-                stack.push(*current.last().unwrap());
-                current = difference(&current);
-            }
-            stack.iter().sum::<i64>()
-        })
-        .sum()
+        .map(|e| e.value())
+        .sum::<BigInt>()
+        .to_i64()
+        .expect("The sum does not fit into an i64.")
+}
+
+pub fn process_part1(input: &str) -> i64 {
+    sum(input, false)
 }
 
 pub fn process_part2(input: &str) -> i64 {
-    let lines = parse_str(input);
-    lines
-        .iter()
-        .map(|l| {
-            let mut stack: Vec<i64> = Vec::new();
-            let mut current: Vec<i64> = l.to_vec();
-            while current.iter().any(|n| *n != 0) {
-                stack.push(*current.first().unwrap());
-                current = difference(&current);
-            }
-            stack.iter().rev().fold(0, |acc, n| n - acc)
-        })
-        .sum()
+    sum(input, true)
 }
 
 #[cfg(test)]
@@ -63,4 +119,25 @@ mod tests {
 10 13 16 21 30 45";
         assert_eq!(2_i64, process_part2(input));
     }
+
+    #[test]
+    fn test_overflow_fallback() {
+        let line = "0 4611686018427387904 -4611686018427387904";
+        assert_eq!(
+            extrapolate_line(line, false),
+            Extrapolation::Overflowed("-27670116110564327424".parse().unwrap())
+        );
+        assert_eq!(
+            extrapolate_line(line, true),
+            Extrapolation::Overflowed("-18446744073709551616".parse().unwrap())
+        );
+        assert_eq!(
+            extrapolate_line("1 3 6 10 15 21", false),
+            Extrapolation::Exact(28)
+        );
+        assert_eq!(
+            extrapolate_line("100000000000000000000 200000000000000000000", false),
+            Extrapolation::Overflowed("300000000000000000000".parse().unwrap())
+        );
+    }
 }
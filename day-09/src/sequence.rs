@@ -0,0 +1,165 @@
+use num::{BigInt, BigRational, One, Zero};
+
+/// A sequence generated by a polynomial, stored as its Newton forward differences
+/// `y(x) = sum_k d_k * binomial(x, k)` where `x` is the index into the sequence.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Sequence {
+    len: usize,
+    /// First entry of every difference row up to the last non zero row.
+    differences: Vec<BigInt>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum SequenceError {
+    Empty,
+    /// The differences did not become zero before running out of values.
+    NotPolynomial,
+}
+
+impl Sequence {
+    pub fn new(values: &[i64]) -> Result<Self, SequenceError> {
+        if values.is_empty() {
+            return Err(SequenceError::Empty);
+        }
+        let mut current: Vec<BigInt> = values.iter().map(|n| BigInt::from(*n)).collect();
+        let mut differences: Vec<BigInt> = Vec::new();
+        while current.iter().any(|n| !n.is_zero()) {
+            if current.len() == 1 {
+                return Err(SequenceError::NotPolynomial);
+            }
+            differences.push(current[0].clone());
+            current = current.windows(2).map(|w| &w[1] - &w[0]).collect();
+        }
+        Ok(Sequence {
+            len: values.len(),
+            differences,
+        })
+    }
+
+    /// Degree of the generating polynomial. The zero sequence has degree 0.
+    pub fn degree(&self) -> usize {
+        self.differences.len().saturating_sub(1)
+    }
+
+    /// Coefficients of the generating polynomial in `x`, lowest power first.
+    pub fn coefficients(&self) -> Vec<BigRational> {
+        let mut coefficients = vec![BigRational::zero(); self.degree() + 1];
+        // Falling factorial x * (x - 1) * ... * (x - k + 1) divided by k!.
+        let mut binomial = vec![BigRational::one()];
+        for (k, d) in self.differences.iter().enumerate() {
+            for (c, b) in coefficients.iter_mut().zip(binomial.iter()) {
+                *c += b * BigRational::from_integer(d.clone());
+            }
+            let k = BigRational::from_integer(BigInt::from(k));
+            let next_k = &k + BigRational::one();
+            let mut next = vec![BigRational::zero(); binomial.len() + 1];
+            for (power, b) in binomial.iter().enumerate() {
+                next[power + 1] += b / &next_k;
+                next[power] -= b * &k / &next_k;
+            }
+            binomial = next;
+        }
+        coefficients
+    }
+
+    /// Value at index `x`. Negative indices lie before the first value.
+    pub fn value_at(&self, x: i64) -> BigInt {
+        let x = BigInt::from(x);
+        let mut binomial = BigInt::one();
+        let mut value = BigInt::zero();
+        for (k, d) in self.differences.iter().enumerate() {
+            value += d * &binomial;
+            let k = BigInt::from(k);
+            binomial = binomial * (&x - &k) / (k + 1);
+        }
+        value
+    }
+
+    /// The `k`-th value after the last one.
+    pub fn next(&self, k: u64) -> BigInt {
+        self.value_at(self.len as i64 - 1 + k as i64)
+    }
+
+    /// The `k`-th value before the first one.
+    pub fn previous(&self, k: u64) -> BigInt {
+        self.value_at(-(k as i64))
+    }
+}
+
+impl std::fmt::Display for Sequence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let terms: Vec<String> = self
+            .coefficients()
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| !c.is_zero())
+            .map(|(power, c)| {
+                let factor = match c {
+                    _ if power == 0 => c.to_string(),
+                    _ if c.is_one() => String::new(),
+                    _ if (-c).is_one() => String::from("-"),
+                    _ => c.to_string(),
+                };
+                match power {
+                    0 => factor,
+                    1 => format!("{}x", factor),
+                    _ => format!("{}x^{}", factor, power),
+                }
+            })
+            .collect();
+        if terms.is_empty() {
+            write!(f, "0")
+        } else {
+            write!(f, "{}", terms.join(" + ").replace("+ -", "- "))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ratio(numer: i64, denom: i64) -> BigRational {
+        BigRational::new(numer.into(), denom.into())
+    }
+
+    #[test]
+    fn test_degree() {
+        assert_eq!(Sequence::new(&[0, 3, 6, 9, 12, 15]).unwrap().degree(), 1);
+        assert_eq!(Sequence::new(&[1, 3, 6, 10, 15, 21]).unwrap().degree(), 2);
+        assert_eq!(
+            Sequence::new(&[10, 13, 16, 21, 30, 45]).unwrap().degree(),
+            3
+        );
+        assert_eq!(Sequence::new(&[0, 0]).unwrap().degree(), 0);
+        assert_eq!(
+            Sequence::new(&[1, 2, 4, 8, 16]),
+            Err(SequenceError::NotPolynomial)
+        );
+        assert_eq!(Sequence::new(&[]), Err(SequenceError::Empty));
+    }
+
+    #[test]
+    fn test_coefficients() {
+        let triangle = Sequence::new(&[1, 3, 6, 10, 15, 21]).unwrap();
+        assert_eq!(
+            triangle.coefficients(),
+            vec![ratio(1, 1), ratio(3, 2), ratio(1, 2)]
+        );
+        assert_eq!(triangle.to_string(), "1 + 3/2x + 1/2x^2");
+        let cubic = Sequence::new(&[-2, -1, 6, 25, 62]).unwrap();
+        assert_eq!(cubic.to_string(), "-2 + x^3");
+        let falling = Sequence::new(&[4, 3, 2]).unwrap();
+        assert_eq!(falling.to_string(), "4 - x");
+    }
+
+    #[test]
+    fn test_predictions() {
+        let sequence = Sequence::new(&[10, 13, 16, 21, 30, 45]).unwrap();
+        assert_eq!(sequence.next(1), BigInt::from(68));
+        assert_eq!(sequence.previous(1), BigInt::from(5));
+        let triangle = Sequence::new(&[1, 3, 6, 10]).unwrap();
+        assert_eq!(triangle.next(1_000_000), BigInt::from(500_004_500_010_i64));
+        assert_eq!(triangle.previous(3), BigInt::from(1));
+    }
+}
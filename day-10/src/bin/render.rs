@@ -0,0 +1,14 @@
+use day_10::render::{render_svg, render_terminal, RenderOptions};
+
+/// Usage: `cargo run --bin render -- [--blank-junk] [output.svg]`
+fn main() {
+    let input = include_str!("../../input1.txt");
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let options = RenderOptions {
+        blank_junk: args.iter().any(|a| a == "--blank-junk"),
+    };
+    match args.iter().find(|a| a.ends_with(".svg")) {
+        Some(path) => std::fs::write(path, render_svg(input, options)).unwrap(),
+        None => print!("{}", render_terminal(input, options)),
+    }
+}
@@ -1,3 +1,6 @@
+pub mod render;
+pub mod validation;
+
 use std::collections::HashSet;
 
 type WalkOutput = Option<(u64, HashSet<(i64, i64)>, HashSet<(i64, i64)>)>;
@@ -220,19 +223,74 @@ fn walk(
     Some((i, insight_nodes, on_cycle))
 }
 
-pub fn process_part1(input: &str) -> u64 {
+fn find_start(field: &[Vec<char>]) -> Option<(i64, i64)> {
+    field.iter().enumerate().find_map(|(x, l)| {
+        l.iter()
+            .position(|c| c == &'S')
+            .map(|y| (x as i64, y as i64))
+    })
+}
+
+/// Tiles of the loop through `start` in walking order, beginning with `start`.
+fn trace_loop(field: &[Vec<char>], start: (i64, i64)) -> Option<Vec<(i64, i64)>> {
+    [
+        Direction::North,
+        Direction::West,
+        Direction::South,
+        Direction::East,
+    ]
+    .iter()
+    .find_map(|dir| {
+        let mut tiles = vec![start];
+        let (dx, dy) = dir.manipulation();
+        let (mut cx, mut cy) = (start.0 + dx, start.1 + dy);
+        let mut came_from = dir.opposite();
+        while (cx, cy) != start {
+            let new_dir = field
+                .get(cx as usize)
+                .and_then(|v| v.get(cy as usize))
+                .and_then(get_dirs)
+                .and_then(|(d1, d2)| get_next_dir(d1, d2, &came_from))?;
+            tiles.push((cx, cy));
+            let (dx, dy) = new_dir.manipulation();
+            (cx, cy) = (cx + dx, cy + dy);
+            came_from = new_dir.opposite();
+        }
+        Some(tiles)
+    })
+}
+
+/// Keeps only the tiles where the loop changes its direction.
+fn corners(tiles: &[(i64, i64)]) -> Vec<(i64, i64)> {
+    (0..tiles.len())
+        .filter(|i| {
+            let prev = tiles[(i + tiles.len() - 1) % tiles.len()];
+            let next = tiles[(i + 1) % tiles.len()];
+            prev.0 != next.0 && prev.1 != next.1
+        })
+        .map(|i| tiles[i])
+        .collect()
+}
+
+/// Corners of the main loop in walking order.
+pub fn loop_polygon(input: &str) -> Vec<(i64, i64)> {
     let field = init_field(input);
-    let (x, y) = field
+    corners(&trace_loop(&field, find_start(&field).unwrap()).unwrap())
+}
+
+/// Twice the area of the polygon, by the shoelace formula.
+fn shoelace(vertices: &[(i64, i64)]) -> i64 {
+    vertices
         .iter()
-        .enumerate()
-        .find(|(_, l)| l.iter().enumerate().any(|(_, c)| c == &'S'))
-        .and_then(|(x, l)| {
-            l.iter()
-                .enumerate()
-                .find(|(_, c)| c == &&'S')
-                .map(|(y, _)| (x, y))
-        })
-        .unwrap();
+        .zip(vertices.iter().cycle().skip(1))
+        .map(|((x1, y1), (x2, y2))| x1 * y2 - x2 * y1)
+        .sum::<i64>()
+        .abs()
+}
+
+pub fn process_part1(input: &str) -> u64 {
+    let field = init_field(input);
+    let (x, y) = find_start(&field).unwrap();
 
     *[
         Direction::North,
@@ -243,14 +301,7 @@ pub fn process_part1(input: &str) -> u64 {
     .iter()
     .flat_map(|dir| {
         let (dx, dy) = dir.manipulation();
-        walk(
-            &field,
-            x as i64 + dx,
-            y as i64 + dy,
-            dir.opposite(),
-            (x as i64, y as i64),
-            false,
-        )
+        walk(&field, x + dx, y + dy, dir.opposite(), (x, y), false)
     })
     .map(|(n, _, _)| n)
     .collect::<Vec<u64>>()
@@ -259,19 +310,25 @@ pub fn process_part1(input: &str) -> u64 {
         / 2
 }
 
+/// Counts the enclosed tiles with Pick's theorem `A = I + B / 2 - 1`, where the loop tiles are
+/// the boundary points `B` of the polygon through their centers.
 pub fn process_part2(input: &str) -> u64 {
     let field = init_field(input);
-    let (x, y) = field
-        .iter()
-        .enumerate()
-        .find(|(_, l)| l.iter().enumerate().any(|(_, c)| c == &'S'))
-        .and_then(|(x, l)| {
-            l.iter()
-                .enumerate()
-                .find(|(_, c)| c == &&'S')
-                .map(|(y, _)| (x, y))
-        })
-        .unwrap();
+    let tiles = trace_loop(&field, find_start(&field).unwrap()).unwrap();
+    let double_area = shoelace(&corners(&tiles));
+    ((double_area - tiles.len() as i64 + 2) / 2) as u64
+}
+
+/// Counts the enclosed tiles by flood filling from the tiles on the inner side of the loop.
+/// Slower than `process_part2`, but useful to cross check it.
+pub fn process_part2_flood_fill(input: &str) -> u64 {
+    flood_fill_inside(&init_field(input)).unwrap().len() as u64
+}
+
+/// Tiles enclosed by the main loop, flood filled from the inner side found by `walk`. `None` if
+/// there is no start or no closed loop through it.
+fn flood_fill_inside(field: &[Vec<char>]) -> Option<HashSet<(i64, i64)>> {
+    let (x, y) = find_start(field)?;
 
     let (_, in_nodes, on_cycle) = [
         Direction::North,
@@ -282,34 +339,24 @@ pub fn process_part2(input: &str) -> u64 {
     .iter()
     .flat_map(|dir| {
         let (dx, dy) = dir.manipulation();
-        walk(
-            &field,
-            x as i64 + dx,
-            y as i64 + dy,
-            dir.opposite(),
-            (x as i64, y as i64),
-            true,
-        )
+        walk(field, x + dx, y + dy, dir.opposite(), (x, y), true)
     })
-    .collect::<Vec<_>>()
-    .first()
-    .unwrap()
-    .clone();
+    .next()?;
     let mut insight_nodes = in_nodes;
     let mut queue: Vec<(i64, i64)> = insight_nodes.clone().into_iter().collect();
     while !queue.is_empty() {
         let (x, y) = &queue.pop().unwrap();
         queue.extend(
-            field_neighbors(&field, *x, *y)
+            field_neighbors(field, *x, *y)
                 .iter()
                 .filter(|point| !insight_nodes.contains(point) && !on_cycle.contains(point)),
         );
         insight_nodes.insert((*x, *y));
     }
-    if insight_nodes.contains(&(x as i64, y as i64)) {
-        insight_nodes.remove(&(x as i64, y as i64));
+    if insight_nodes.contains(&(x, y)) {
+        insight_nodes.remove(&(x, y));
     };
-    insight_nodes.len() as u64
+    Some(insight_nodes)
 }
 
 #[cfg(test)]
@@ -424,6 +471,30 @@ L---J.";
         assert_eq!(9_u64, process_part2(input));
     }
 
+    #[test]
+    fn test_polygon() {
+        let input = ".....
+.S-7.
+.|.|.
+.L-J.
+.....";
+        assert_eq!(loop_polygon(input), vec![(1, 1), (3, 1), (3, 3), (1, 3)]);
+        assert_eq!(loop_polygon("S7\nLJ"), vec![(0, 0), (1, 0), (1, 1), (0, 1)]);
+    }
+
+    #[test]
+    fn test_flood_fill_agrees() {
+        let inputs = [
+            "S-7\n|.|\nL-J",
+            "S---7.\n|F-7|.\n||-||.\n|L-J|.\nL---J.",
+            "..........\n.S------7.\n.|F----7|.\n.||....||.\n.||....||.\n.|L-7F-J|.\n.|..||..|.\n.L--JL--J.\n..........",
+            "FF7FSF7F7F7F7F7F---7\nL|LJ||||||||||||F--J\nFL-7LJLJ||||||LJL-77\nF--JF--7||LJLJ7F7FJ-\nL---JF-JLJ.||-FJLJJ7\n|F|F-JF---7F7-L7L|7|\n|FFJF7L7F-JF7|JL---7\n7-L-JL7||F7|L7F-7F7|\nL.L7LFJ|||||FJL7||LJ\nL7JLJL-JLJLJL--JLJ.L",
+        ];
+        for input in inputs {
+            assert_eq!(process_part2(input), process_part2_flood_fill(input));
+        }
+    }
+
     #[test]
     fn test_neighbors() {
         assert_eq!(
@@ -0,0 +1,209 @@
+use std::collections::HashSet;
+use std::fmt::Write;
+
+use crate::validation::{infer_start, StartShape};
+use crate::{find_start, flood_fill_inside, get_dirs, init_field, trace_loop, Direction};
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct RenderOptions {
+    /// Hide pipes which are not part of the main loop.
+    pub blank_junk: bool,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Class {
+    Loop,
+    Inside,
+    Outside,
+}
+
+const ANSI_LOOP: &str = "\x1b[1;33m";
+const ANSI_INSIDE: &str = "\x1b[42m";
+const ANSI_JUNK: &str = "\x1b[2m";
+const ANSI_RESET: &str = "\x1b[0m";
+
+fn glyph(c: char, on_loop: bool) -> char {
+    match (c, on_loop) {
+        ('|', false) => '│',
+        ('-', false) => '─',
+        ('L', false) => '└',
+        ('J', false) => '┘',
+        ('7', false) => '┐',
+        ('F', false) => '┌',
+        ('|', true) => '┃',
+        ('-', true) => '━',
+        ('L', true) => '┗',
+        ('J', true) => '┛',
+        ('7', true) => '┓',
+        ('F', true) => '┏',
+        ('.', _) => ' ',
+        (c, _) => c,
+    }
+}
+
+/// The field with `S` replaced by its pipe and every tile classified by the flood fill of `walk`.
+/// Without a start or a closed loop through it, every tile is outside.
+fn classify(input: &str) -> (Vec<Vec<char>>, Vec<Vec<Class>>) {
+    let mut field = init_field(input);
+    let main_loop = find_start(&field).and_then(|start| {
+        Some((
+            start,
+            trace_loop(&field, start)?,
+            flood_fill_inside(&field)?,
+        ))
+    });
+    let (mut on_loop, mut inside) = (HashSet::new(), HashSet::new());
+    if let Some((start, tiles, filled)) = main_loop {
+        if let StartShape::Pipe(c) = infer_start(input) {
+            field[start.0 as usize][start.1 as usize] = c;
+        }
+        on_loop = tiles.into_iter().collect();
+        inside = filled;
+    }
+    let classes = field
+        .iter()
+        .enumerate()
+        .map(|(x, l)| {
+            (0..l.len())
+                .map(|y| {
+                    let pos = (x as i64, y as i64);
+                    if on_loop.contains(&pos) {
+                        Class::Loop
+                    } else if inside.contains(&pos) {
+                        Class::Inside
+                    } else {
+                        Class::Outside
+                    }
+                })
+                .collect()
+        })
+        .collect();
+    (field, classes)
+}
+
+/// Draws the field with box drawing characters and ANSI colors. The main loop is bold and
+/// yellow, inside tiles have a green background and other pipes are dimmed.
+pub fn render_terminal(input: &str, options: RenderOptions) -> String {
+    let (field, classes) = classify(input);
+    let mut out = String::new();
+    for (line, classes) in field.iter().zip(classes.iter()) {
+        for (c, class) in line.iter().zip(classes.iter()) {
+            let is_pipe = get_dirs(c).is_some();
+            match class {
+                Class::Loop => write!(out, "{}{}{}", ANSI_LOOP, glyph(*c, true), ANSI_RESET),
+                Class::Inside if is_pipe && !options.blank_junk => {
+                    write!(out, "{}{}{}", ANSI_INSIDE, glyph(*c, false), ANSI_RESET)
+                }
+                Class::Inside => write!(out, "{} {}", ANSI_INSIDE, ANSI_RESET),
+                Class::Outside if is_pipe && !options.blank_junk => {
+                    write!(out, "{}{}{}", ANSI_JUNK, glyph(*c, false), ANSI_RESET)
+                }
+                Class::Outside => write!(out, " "),
+            }
+            .unwrap();
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Draws the field as SVG with one 10x10 cell per tile and the pipes as lines.
+pub fn render_svg(input: &str, options: RenderOptions) -> String {
+    const CELL: i64 = 10;
+    let (field, classes) = classify(input);
+    let width = field.iter().map(|l| l.len()).max().unwrap_or(0) as i64 * CELL;
+    let height = field.len() as i64 * CELL;
+    let mut out = String::new();
+    writeln!(
+        out,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\">",
+        width, height
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "<rect width=\"{}\" height=\"{}\" fill=\"white\"/>",
+        width, height
+    )
+    .unwrap();
+    for (x, (line, classes)) in field.iter().zip(classes.iter()).enumerate() {
+        for (y, (c, class)) in line.iter().zip(classes.iter()).enumerate() {
+            let (top, left) = (x as i64 * CELL, y as i64 * CELL);
+            if class == &Class::Inside {
+                writeln!(
+                    out,
+                    "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"lightgreen\"/>",
+                    left, top, CELL, CELL
+                )
+                .unwrap();
+            }
+            let Some((d1, d2)) = get_dirs(c) else {
+                continue;
+            };
+            let (stroke, width) = match class {
+                Class::Loop => ("red", 3),
+                _ if options.blank_junk => continue,
+                _ => ("gray", 1),
+            };
+            let (cx, cy) = (left + CELL / 2, top + CELL / 2);
+            let end = |d: &Direction| {
+                let (dx, dy) = d.manipulation();
+                (cx + dy * CELL / 2, cy + dx * CELL / 2)
+            };
+            let ((x1, y1), (x2, y2)) = (end(d1), end(d2));
+            writeln!(
+                out,
+                "<polyline points=\"{},{} {},{} {},{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"{}\"/>",
+                x1, y1, cx, cy, x2, y2, stroke, width
+            )
+            .unwrap();
+        }
+    }
+    out.push_str("</svg>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_terminal() {
+        let input = "S-7F\n|.|.\nL-J.";
+        let plain = render_terminal(input, RenderOptions { blank_junk: true })
+            .replace(ANSI_LOOP, "")
+            .replace(ANSI_INSIDE, "")
+            .replace(ANSI_RESET, "");
+        assert_eq!(plain, "┏━┓ \n┃ ┃ \n┗━┛ \n");
+        let with_junk = render_terminal(input, RenderOptions::default());
+        assert!(with_junk.contains(&format!("{}┌{}", ANSI_JUNK, ANSI_RESET)));
+        assert!(with_junk.contains(&format!("{} {}", ANSI_INSIDE, ANSI_RESET)));
+    }
+
+    #[test]
+    fn test_render_svg() {
+        let input = "S-7F\n|.|.\nL-J.";
+        let svg = render_svg(input, RenderOptions::default());
+        assert!(svg
+            .starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"40\" height=\"30\">"));
+        assert_eq!(svg.matches("stroke=\"red\"").count(), 8);
+        assert_eq!(svg.matches("stroke=\"gray\"").count(), 1);
+        assert_eq!(svg.matches("fill=\"lightgreen\"").count(), 1);
+        let svg = render_svg(input, RenderOptions { blank_junk: true });
+        assert_eq!(svg.matches("stroke=\"gray\"").count(), 0);
+    }
+
+    #[test]
+    fn test_render_without_loop() {
+        let plain = |input| {
+            render_terminal(input, RenderOptions::default())
+                .replace(ANSI_JUNK, "")
+                .replace(ANSI_RESET, "")
+        };
+        assert_eq!(plain("F-7\n|.|\nL-J"), "┌─┐\n│ │\n└─┘\n");
+        assert_eq!(plain("S-7\n|.|\nL-."), " ─┐\n│ │\n└─ \n");
+        let svg = render_svg("S-7\n|.|\nL-.", RenderOptions::default());
+        assert_eq!(svg.matches("stroke=\"red\"").count(), 0);
+        assert_eq!(svg.matches("fill=\"lightgreen\"").count(), 0);
+    }
+}
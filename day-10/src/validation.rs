@@ -0,0 +1,200 @@
+use crate::{find_start, get_dirs, get_next_dir, init_field, Direction, POSSIBLE_DIRECTIONS};
+
+const DIRECTIONS: [Direction; 4] = [
+    Direction::North,
+    Direction::West,
+    Direction::South,
+    Direction::East,
+];
+
+/// The pipe hidden under `S`.
+#[derive(Debug, PartialEq, Eq)]
+pub enum StartShape {
+    Pipe(char),
+    /// Several pipes close a loop through `S`.
+    Ambiguous(Vec<char>),
+    /// No pipe closes a loop through `S`.
+    Impossible,
+    /// The field has no `S`.
+    Missing,
+}
+
+/// Problems of a pipe field. `S` is replaced by its inferred pipe if it is unambiguous.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Report {
+    pub start: StartShape,
+    /// Pipes with an end not connected to a matching pipe, in reading order.
+    pub dangling: Vec<(i64, i64)>,
+    /// Every closed loop in walking order, starting at its first tile in reading order.
+    pub loops: Vec<Vec<(i64, i64)>>,
+}
+
+fn tile(field: &[Vec<char>], (x, y): (i64, i64)) -> Option<char> {
+    if x < 0 || y < 0 {
+        return None;
+    }
+    field
+        .get(x as usize)
+        .and_then(|v| v.get(y as usize))
+        .copied()
+}
+
+fn shift((x, y): (i64, i64), dir: &Direction) -> (i64, i64) {
+    let (dx, dy) = dir.manipulation();
+    (x + dx, y + dy)
+}
+
+fn ends(field: &[Vec<char>], pos: (i64, i64)) -> Option<(Direction, Direction)> {
+    tile(field, pos).as_ref().and_then(get_dirs).cloned()
+}
+
+/// Whether the tile at `pos` has a pipe end pointing to `dir`.
+fn has_end(field: &[Vec<char>], pos: (i64, i64), dir: &Direction) -> bool {
+    ends(field, pos)
+        .and_then(|(d1, d2)| get_next_dir(&d1, &d2, dir))
+        .is_some()
+}
+
+fn connected(field: &[Vec<char>], pos: (i64, i64), dir: &Direction) -> bool {
+    has_end(field, pos, dir) && has_end(field, shift(pos, dir), &dir.opposite())
+}
+
+/// Follows the pipes leaving `start` to `dir` and returns the direction the walk enters `start`
+/// from, if it gets back there.
+fn returns_from(field: &[Vec<char>], start: (i64, i64), dir: &Direction) -> Option<Direction> {
+    let mut pos = shift(start, dir);
+    let mut came_from = dir.opposite();
+    while pos != start {
+        let new_dir = ends(field, pos).and_then(|(d1, d2)| get_next_dir(&d1, &d2, &came_from))?;
+        pos = shift(pos, &new_dir);
+        came_from = new_dir.opposite();
+    }
+    Some(came_from)
+}
+
+fn pipe_of(d1: &Direction, d2: &Direction) -> char {
+    POSSIBLE_DIRECTIONS
+        .iter()
+        .find(|(_, (a, b))| (a == d1 && b == d2) || (a == d2 && b == d1))
+        .map(|(c, _)| *c)
+        .unwrap()
+}
+
+fn infer_start_in(field: &[Vec<char>]) -> StartShape {
+    let Some(start) = find_start(field) else {
+        return StartShape::Missing;
+    };
+    let mut shapes: Vec<char> = DIRECTIONS
+        .iter()
+        .filter(|dir| has_end(field, shift(start, dir), &dir.opposite()))
+        .filter_map(|dir| returns_from(field, start, dir).map(|back| pipe_of(dir, &back)))
+        .collect();
+    shapes.sort();
+    shapes.dedup();
+    match shapes.len() {
+        0 => StartShape::Impossible,
+        1 => StartShape::Pipe(shapes[0]),
+        _ => StartShape::Ambiguous(shapes),
+    }
+}
+
+/// Infers which pipe `S` stands for from the loops it can close.
+pub fn infer_start(input: &str) -> StartShape {
+    infer_start_in(&init_field(input))
+}
+
+pub fn validate(input: &str) -> Report {
+    let mut field = init_field(input);
+    let start = infer_start_in(&field);
+    if let (StartShape::Pipe(c), Some((x, y))) = (&start, find_start(&field)) {
+        field[x as usize][y as usize] = *c;
+    }
+    let positions: Vec<(i64, i64)> = (0..field.len() as i64)
+        .flat_map(|x| (0..field[x as usize].len() as i64).map(move |y| (x, y)))
+        .filter(|pos| ends(&field, *pos).is_some())
+        .collect();
+    let dangling: Vec<(i64, i64)> = positions
+        .iter()
+        .filter(|pos| {
+            DIRECTIONS
+                .iter()
+                .any(|dir| has_end(&field, **pos, dir) && !connected(&field, **pos, dir))
+        })
+        .copied()
+        .collect();
+
+    let mut seen = vec![vec![false; field.iter().map(|l| l.len()).max().unwrap_or(0)]; field.len()];
+    let mut loops = Vec::new();
+    for pos in positions {
+        if seen[pos.0 as usize][pos.1 as usize] {
+            continue;
+        }
+        let (d1, _) = ends(&field, pos).unwrap();
+        let mut tiles = vec![pos];
+        let mut current = shift(pos, &d1);
+        let mut came_from = d1.opposite();
+        let mut closed = connected(&field, pos, &d1);
+        while closed && current != pos {
+            tiles.push(current);
+            let (a, b) = ends(&field, current).unwrap();
+            let new_dir = get_next_dir(&a, &b, &came_from).unwrap();
+            closed = connected(&field, current, &new_dir);
+            current = shift(current, &new_dir);
+            came_from = new_dir.opposite();
+        }
+        for (x, y) in &tiles {
+            seen[*x as usize][*y as usize] = true;
+        }
+        if closed {
+            loops.push(tiles);
+        }
+    }
+    Report {
+        start,
+        dangling,
+        loops,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_infer_start() {
+        assert_eq!(
+            infer_start(".....\n.S-7.\n.|.|.\n.L-J.\n....."),
+            StartShape::Pipe('F')
+        );
+        assert_eq!(infer_start("FS\nLJ"), StartShape::Pipe('7'));
+        assert_eq!(infer_start("-S-\n..."), StartShape::Impossible);
+        assert_eq!(
+            infer_start("F-7F7\n|.LSJ\nL-7|.\n..LJ."),
+            StartShape::Ambiguous(vec!['7', 'L'])
+        );
+        assert_eq!(infer_start("F7\nLJ"), StartShape::Missing);
+    }
+
+    #[test]
+    fn test_validate() {
+        let report = validate("S-7.F7\n|.|.LJ\nL-J-7.");
+        assert_eq!(report.start, StartShape::Pipe('F'));
+        assert_eq!(report.dangling, vec![(2, 3), (2, 4)]);
+        assert_eq!(
+            report.loops,
+            vec![
+                vec![
+                    (0, 0),
+                    (0, 1),
+                    (0, 2),
+                    (1, 2),
+                    (2, 2),
+                    (2, 1),
+                    (2, 0),
+                    (1, 0)
+                ],
+                vec![(0, 4), (0, 5), (1, 5), (1, 4)],
+            ]
+        );
+    }
+}
@@ -1,3 +1,5 @@
+pub mod query;
+
 use std::str::FromStr;
 
 use itertools::Itertools;
@@ -130,26 +132,50 @@ pub fn process_part2(input: &str) -> u64 {
     process_part2_with_factor(input, 1_000_000)
 }
 
-fn process_part2_with_factor(input: &str, factor: usize) -> u64 {
-    let (universum_map, scale_x, scale_y) = UniversumMap::universum_map_unexpanded(input);
-    universum_map
-        .galaxy_cords
+/// Coordinates along one axis after every empty line before them grew to `factor` lines.
+/// `empty` has to be sorted.
+fn expand_axis(coords: impl Iterator<Item = usize>, empty: &[usize], factor: usize) -> Vec<u128> {
+    coords
+        .map(|c| {
+            let empty_before = empty.partition_point(|e| *e < c);
+            c as u128 + empty_before as u128 * (factor as u128 - 1)
+        })
+        .collect()
+}
+
+/// Sum of `|a - b|` over all pairs. After sorting, every coordinate is the larger one in all pairs
+/// with the coordinates before it, so it adds `c * i - (sum of the i coordinates before it)`.
+fn pairwise_distance_sum(mut coords: Vec<u128>) -> u128 {
+    coords.sort_unstable();
+    let mut prefix: u128 = 0;
+    coords
         .iter()
-        .tuple_combinations()
-        .map(|(g1, g2)| -> usize {
-            let passed_y: Vec<&usize> = scale_y
-                .iter()
-                .filter(|&&x| g1.0 <= x && x <= g2.0 || g2.0 <= x && x <= g1.0)
-                .collect();
-            let passed_x: Vec<&usize> = scale_x
-                .iter()
-                .filter(|&&y| g1.1 <= y && y <= g2.1 || g2.1 <= y && y <= g1.1)
-                .collect();
-            g1.0.abs_diff(g2.0)
-                + g1.1.abs_diff(g2.1)
-                + (passed_x.len() + passed_y.len()) * (factor - 1)
+        .enumerate()
+        .map(|(i, c)| {
+            let sum = c * i as u128 - prefix;
+            prefix += c;
+            sum
         })
-        .sum::<usize>() as u64
+        .sum()
+}
+
+/// Sum of the Manhattan distances between all galaxy pairs after every empty row and column grew
+/// to `factor` rows or columns. Runs in `O(n log n)` for `n` galaxies.
+pub fn process_part2_with_factor(input: &str, factor: usize) -> u64 {
+    let (universum_map, empty_columns, empty_rows) = UniversumMap::universum_map_unexpanded(input);
+    let rows = expand_axis(
+        universum_map.galaxy_cords.iter().map(|g| g.0),
+        &empty_rows,
+        factor,
+    );
+    let columns = expand_axis(
+        universum_map.galaxy_cords.iter().map(|g| g.1),
+        &empty_columns,
+        factor,
+    );
+    (pairwise_distance_sum(rows) + pairwise_distance_sum(columns))
+        .try_into()
+        .expect("The distance sum does not fit into an u64.")
 }
 
 #[cfg(test)]
@@ -206,4 +232,30 @@ mod tests {
         let input = "#.#";
         assert_eq!(11_u64, process_part2_with_factor(input, 10));
     }
+
+    #[test]
+    fn test_factor_2_matches_part1() {
+        let input = "...#......
+.......#..
+#.........
+..........
+......#...
+.#........
+.........#
+..........
+.......#..
+#...#.....";
+        assert_eq!(process_part1(input), process_part2_with_factor(input, 2));
+        assert_eq!(82000210_u64, process_part2_with_factor(input, 1_000_000));
+    }
+
+    #[test]
+    fn test_pairwise_distance_sum() {
+        assert_eq!(pairwise_distance_sum(vec![5, 1, 3]), 8);
+        assert_eq!(pairwise_distance_sum(vec![]), 0);
+        assert_eq!(
+            expand_axis([0, 2, 5].into_iter(), &[1, 3, 4], 10),
+            vec![0, 11, 32]
+        );
+    }
 }
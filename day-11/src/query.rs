@@ -0,0 +1,220 @@
+use std::cmp::Ordering;
+
+use crate::{expand_axis, UniversumMap};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Metric {
+    Manhattan,
+    Chebyshev,
+    Euclidean,
+}
+
+/// Distance between two galaxies. Manhattan and Chebyshev distances are whole numbers and are
+/// kept exact, Euclidean ones are rounded.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Distance {
+    Exact(u128),
+    Rounded(f64),
+}
+
+impl Distance {
+    pub fn as_f64(self) -> f64 {
+        match self {
+            Distance::Exact(d) => d as f64,
+            Distance::Rounded(d) => d,
+        }
+    }
+
+    /// Orders exact distances exactly and everything else by `f64::total_cmp`.
+    pub fn total_cmp(&self, other: &Distance) -> Ordering {
+        match (self, other) {
+            (Distance::Exact(a), Distance::Exact(b)) => a.cmp(b),
+            _ => self.as_f64().total_cmp(&other.as_f64()),
+        }
+    }
+}
+
+/// Most bins `Galaxies::histogram` fills, so that a tiny bin width fails instead of allocating
+/// a huge vector.
+pub const MAX_BINS: usize = 1 << 20;
+
+/// Galaxies at their expanded coordinates, numbered in reading order.
+pub struct Galaxies {
+    positions: Vec<(u128, u128)>,
+}
+
+impl Galaxies {
+    /// Every empty row grows to `row_factor` rows and every empty column to `column_factor` columns.
+    pub fn new(input: &str, row_factor: usize, column_factor: usize) -> Self {
+        let (universum_map, empty_columns, empty_rows) =
+            UniversumMap::universum_map_unexpanded(input);
+        let rows = expand_axis(
+            universum_map.galaxy_cords.iter().map(|g| g.0),
+            &empty_rows,
+            row_factor,
+        );
+        let columns = expand_axis(
+            universum_map.galaxy_cords.iter().map(|g| g.1),
+            &empty_columns,
+            column_factor,
+        );
+        Galaxies {
+            positions: rows.into_iter().zip(columns).collect(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    /// Row and column of galaxy `i`.
+    pub fn position(&self, i: usize) -> (u128, u128) {
+        self.positions[i]
+    }
+
+    pub fn distance(&self, a: usize, b: usize, metric: Metric) -> Distance {
+        let (ra, ca) = self.positions[a];
+        let (rb, cb) = self.positions[b];
+        let (dr, dc) = (ra.abs_diff(rb), ca.abs_diff(cb));
+        match metric {
+            Metric::Manhattan => Distance::Exact(dr + dc),
+            Metric::Chebyshev => Distance::Exact(dr.max(dc)),
+            Metric::Euclidean => Distance::Rounded((dr as f64).hypot(dc as f64)),
+        }
+    }
+
+    /// The `k` galaxies closest to galaxy `i` with their distances, closest first.
+    /// Ties are broken by the galaxy number.
+    pub fn nearest(&self, i: usize, k: usize, metric: Metric) -> Vec<(usize, Distance)> {
+        let mut others: Vec<(usize, Distance)> = (0..self.len())
+            .filter(|j| *j != i)
+            .map(|j| (j, self.distance(i, j, metric)))
+            .collect();
+        others.sort_by(|a, b| a.1.total_cmp(&b.1).then(a.0.cmp(&b.0)));
+        others.truncate(k);
+        others
+    }
+
+    /// Number of galaxy pairs per distance bin, where bin `n` covers `[n * bin_width, (n + 1) * bin_width)`.
+    /// The bin width has to be positive and finite, and wide enough for at most `MAX_BINS` bins.
+    pub fn histogram(&self, metric: Metric, bin_width: f64) -> Result<Vec<usize>, String> {
+        if !(bin_width > 0.0 && bin_width.is_finite()) {
+            return Err(format!(
+                "The bin width {} is not a positive finite number",
+                bin_width
+            ));
+        }
+        let pair_bins: Vec<f64> = (0..self.len())
+            .flat_map(|a| (a + 1..self.len()).map(move |b| (a, b)))
+            .map(|(a, b)| (self.distance(a, b, metric).as_f64() / bin_width).floor())
+            .collect();
+        let last = pair_bins.iter().copied().fold(-1.0, f64::max);
+        if last >= MAX_BINS as f64 {
+            return Err(format!(
+                "The bin width {} needs more than {} bins",
+                bin_width, MAX_BINS
+            ));
+        }
+        let mut bins = vec![0; (last + 1.0) as usize];
+        for bin in pair_bins {
+            bins[bin as usize] += 1;
+        }
+        Ok(bins)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "...#......
+.......#..
+#.........
+..........
+......#...
+.#........
+.........#
+..........
+.......#..
+#...#.....";
+
+    #[test]
+    fn test_distance() {
+        let galaxies = Galaxies::new(INPUT, 2, 2);
+        assert_eq!(galaxies.len(), 9);
+        assert_eq!(
+            galaxies.distance(4, 8, Metric::Manhattan),
+            Distance::Exact(9)
+        );
+        assert_eq!(
+            galaxies.distance(0, 6, Metric::Manhattan),
+            Distance::Exact(15)
+        );
+        assert_eq!(
+            galaxies.distance(0, 6, Metric::Chebyshev),
+            Distance::Exact(10)
+        );
+        assert_eq!(
+            galaxies.distance(2, 5, Metric::Manhattan),
+            Distance::Exact(17)
+        );
+        assert_eq!(
+            galaxies.distance(7, 8, Metric::Euclidean),
+            Distance::Rounded(5.0)
+        );
+        let pair_sum: f64 = (0..9)
+            .flat_map(|a| (a + 1..9).map(move |b| (a, b)))
+            .map(|(a, b)| galaxies.distance(a, b, Metric::Manhattan).as_f64())
+            .sum();
+        assert_eq!(pair_sum, 374.0);
+    }
+
+    #[test]
+    fn test_exact_distance() {
+        // Beyond 2^53, where consecutive integers are no longer distinct as f64
+        let galaxies = Galaxies::new("#..\n..#", 1, (1 << 54) + 1);
+        assert_eq!(
+            galaxies.distance(0, 1, Metric::Manhattan),
+            Distance::Exact((1 << 54) + 3)
+        );
+    }
+
+    #[test]
+    fn test_per_axis_expansion() {
+        let galaxies = Galaxies::new("#.#\n...\n#..", 10, 1);
+        assert_eq!(galaxies.position(2), (11, 0));
+        assert_eq!(galaxies.position(1), (0, 2));
+        let galaxies = Galaxies::new("#.#\n...\n#..", 1, 10);
+        assert_eq!(galaxies.position(1), (0, 11));
+    }
+
+    #[test]
+    fn test_nearest_and_histogram() {
+        let galaxies = Galaxies::new("#.#\n...\n#..", 1, 1);
+        assert_eq!(
+            galaxies.nearest(0, 2, Metric::Manhattan),
+            vec![(1, Distance::Exact(2)), (2, Distance::Exact(2))]
+        );
+        assert_eq!(
+            galaxies.nearest(1, 1, Metric::Chebyshev),
+            vec![(0, Distance::Exact(2))]
+        );
+        assert_eq!(
+            galaxies.histogram(Metric::Manhattan, 1.0),
+            Ok(vec![0, 0, 2, 0, 1])
+        );
+        assert_eq!(galaxies.histogram(Metric::Euclidean, 2.0), Ok(vec![0, 3]));
+        assert!(galaxies.histogram(Metric::Euclidean, 0.0).is_err());
+        assert!(galaxies.histogram(Metric::Euclidean, -1.0).is_err());
+        assert!(galaxies.histogram(Metric::Euclidean, f64::NAN).is_err());
+        assert!(galaxies.histogram(Metric::Manhattan, 1e-300).is_err());
+        assert_eq!(
+            Galaxies::new("#", 1, 1).histogram(Metric::Manhattan, 1.0),
+            Ok(vec![])
+        );
+    }
+}
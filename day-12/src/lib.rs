@@ -1,5 +1,10 @@
+use num::{bigint::RandBigInt, BigUint, One, Zero};
+use rand::Rng;
 use std::{collections::HashMap, str::FromStr};
 
+pub mod nonogram;
+pub mod unfold;
+
 #[derive(Clone, PartialEq, Debug, Copy)]
 enum State {
     Good,
@@ -7,7 +12,7 @@ enum State {
     Unknown,
 }
 
-struct Line {
+pub struct Line {
     springs: Vec<State>,
     backup: Vec<usize>,
 }
@@ -38,80 +43,242 @@ impl FromStr for Line {
 }
 
 impl Line {
-    fn dp(&self, pos: usize, at_group: usize, cache: &mut HashMap<(usize, usize), u64>) -> u64 {
+    fn dp(
+        &self,
+        pos: usize,
+        at_group: usize,
+        cache: &mut HashMap<(usize, usize), BigUint>,
+    ) -> BigUint {
         if let Some(res) = cache.get(&(pos, at_group)) {
-            return *res;
+            return res.clone();
         }
         let spring_op = self.springs.get(pos);
         if let Some(spring) = spring_op {
-            let mut res = 0;
+            let mut res = BigUint::zero();
             if spring == &State::Good || spring == &State::Unknown {
                 // Let spring be good
                 res += self.dp(pos + 1, at_group, cache);
             }
             if (spring == &State::Unknown || spring == &State::Broken)
                 && at_group < self.backup.len()
+                && self.group_fits(pos, *self.backup.get(at_group).unwrap())
             {
-                let check_group = if let Some(group) = self
-                    .springs
-                    .get(pos..pos + self.backup.get(at_group).unwrap())
-                {
-                    // Can take group
-                    !group.contains(&State::Good)
-                } else {
-                    false
-                };
-                let check_group_end = if let Some(next_spring) =
-                    self.springs.get(pos + self.backup.get(at_group).unwrap())
-                {
-                    next_spring != &State::Broken
-                } else {
-                    true
-                };
-                if check_group && check_group_end {
-                    res += self.dp(
-                        pos + self.backup.get(at_group).unwrap() + 1,
-                        at_group + 1,
-                        cache,
-                    );
-                }
+                // Let the next group start here
+                res += self.dp(
+                    pos + self.backup.get(at_group).unwrap() + 1,
+                    at_group + 1,
+                    cache,
+                );
             }
-            cache.insert((pos, at_group), res);
+            cache.insert((pos, at_group), res.clone());
             res
         } else {
             // End of springs => check if all groups were taken
-            if at_group == self.backup.len() {
-                cache.insert((pos, at_group), 1);
-                1
+            let res = if at_group == self.backup.len() {
+                BigUint::one()
             } else {
-                cache.insert((pos, at_group), 0);
-                0
+                BigUint::zero()
+            };
+            cache.insert((pos, at_group), res.clone());
+            res
+        }
+    }
+
+    /// Whether a group of `len` broken springs can start at `pos` and end before a spring that
+    /// can be good.
+    fn group_fits(&self, pos: usize, len: usize) -> bool {
+        let check_group = if let Some(group) = self.springs.get(pos..pos + len) {
+            !group.contains(&State::Good)
+        } else {
+            false
+        };
+        let check_group_end = if let Some(next_spring) = self.springs.get(pos + len) {
+            next_spring != &State::Broken
+        } else {
+            true
+        };
+        check_group && check_group_end
+    }
+
+    /// Number of valid arrangements.
+    pub fn count(&self) -> BigUint {
+        self.dp(0, 0, &mut HashMap::new())
+    }
+
+    /// Ways to continue from `(pos, at_group)`: either the spring at `pos` is good, or the next
+    /// group starts there. Yields the written springs and the state after them.
+    fn choices(&self, pos: usize, at_group: usize) -> Vec<(String, usize, usize)> {
+        let mut choices = Vec::new();
+        match self.springs.get(pos) {
+            Some(State::Broken) => (),
+            Some(_) => choices.push((String::from("."), pos + 1, at_group)),
+            None => return choices,
+        }
+        match self.backup.get(at_group) {
+            Some(len) if self.group_fits(pos, *len) => {
+                let written = if pos + len < self.springs.len() {
+                    format!("{}.", "#".repeat(*len))
+                } else {
+                    "#".repeat(*len)
+                };
+                choices.push((written, pos + len + 1, at_group + 1));
             }
+            _ => (),
         }
+        choices
     }
 
-    fn expand(&self) -> Line {
+    /// Lazily yields every valid arrangement, written with `.` and `#`.
+    pub fn arrangements(&self) -> Arrangements<'_> {
+        Arrangements {
+            line: self,
+            cache: HashMap::new(),
+            stack: vec![(String::new(), 0, 0)],
+        }
+    }
+
+    /// The arrangement at `index` in the order of `arrangements`, found by skipping whole
+    /// branches with the counts of `dp`.
+    pub fn arrangement(&self, index: &BigUint) -> Option<String> {
+        let mut cache = HashMap::new();
+        let (mut written, mut pos, mut at_group) = (String::new(), 0, 0);
+        if index >= &self.dp(pos, at_group, &mut cache) {
+            return None;
+        }
+        let mut index = index.clone();
+        while pos < self.springs.len() {
+            for (w, next_pos, next_group) in self.choices(pos, at_group) {
+                let count = self.dp(next_pos, next_group, &mut cache);
+                if index < count {
+                    written.push_str(&w);
+                    (pos, at_group) = (next_pos, next_group);
+                    break;
+                }
+                index -= count;
+            }
+        }
+        Some(written)
+    }
+
+    /// Draws an arrangement uniformly at random, or `None` if there is none.
+    pub fn sample<R: Rng>(&self, rng: &mut R) -> Option<String> {
+        let count = self.count();
+        if count.is_zero() {
+            return None;
+        }
+        self.arrangement(&rng.gen_biguint_below(&count))
+    }
+
+    /// The springs that are the same in every valid arrangement, with `Unknown` for the ones
+    /// that differ. `None` if there is no valid arrangement.
+    fn resolve(&self) -> Option<Vec<State>> {
+        let mut cache = HashMap::new();
+        if self.dp(0, 0, &mut cache).is_zero() {
+            return None;
+        }
+        let mut can_be_good = vec![false; self.springs.len()];
+        let mut can_be_broken = vec![false; self.springs.len()];
+        // Walk forward through the states that lie on some valid arrangement
+        let mut reached = vec![vec![false; self.backup.len() + 1]; self.springs.len() + 2];
+        reached[0][0] = true;
+        for pos in 0..self.springs.len() {
+            for at_group in 0..=self.backup.len() {
+                if !reached[pos][at_group] {
+                    continue;
+                }
+                for (written, next_pos, next_group) in self.choices(pos, at_group) {
+                    if self.dp(next_pos, next_group, &mut cache).is_zero() {
+                        continue;
+                    }
+                    for (i, c) in written.chars().enumerate() {
+                        if c == '#' {
+                            can_be_broken[pos + i] = true;
+                        } else {
+                            can_be_good[pos + i] = true;
+                        }
+                    }
+                    reached[next_pos][next_group] = true;
+                }
+            }
+        }
+        let resolved = can_be_good
+            .into_iter()
+            .zip(can_be_broken)
+            .map(|states| match states {
+                (true, false) => State::Good,
+                (false, true) => State::Broken,
+                _ => State::Unknown,
+            })
+            .collect();
+        Some(resolved)
+    }
+
+    /// Unfolds the record into `n` copies joined by unknown springs.
+    pub fn expand(&self, n: usize) -> Line {
         let mut springs = self.springs.clone();
         springs.push(State::Unknown);
-        springs = springs.repeat(5);
+        springs = springs.repeat(n);
         springs.pop();
-        let backup = self.backup.repeat(5);
+        let backup = self.backup.repeat(n);
         Line { springs, backup }
     }
 }
 
-pub fn process_part1(input: &str) -> u64 {
+pub struct Arrangements<'a> {
+    line: &'a Line,
+    cache: HashMap<(usize, usize), BigUint>,
+    /// Partial arrangements with at least one valid completion.
+    stack: Vec<(String, usize, usize)>,
+}
+
+impl Iterator for Arrangements<'_> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.line.dp(0, 0, &mut self.cache).is_zero() {
+            self.stack.clear();
+        }
+        while let Some((written, pos, at_group)) = self.stack.pop() {
+            if pos >= self.line.springs.len() {
+                return Some(written);
+            }
+            let choices = self.line.choices(pos, at_group);
+            for (w, next_pos, next_group) in choices.into_iter().rev() {
+                if !self
+                    .line
+                    .dp(next_pos, next_group, &mut self.cache)
+                    .is_zero()
+                {
+                    self.stack
+                        .push((format!("{}{}", written, w), next_pos, next_group));
+                }
+            }
+        }
+        None
+    }
+}
+
+pub fn process_part1(input: &str) -> BigUint {
     let lines: Vec<Line> = input.lines().flat_map(Line::from_str).collect();
-    lines.iter().map(|l| l.dp(0, 0, &mut HashMap::new())).sum()
+    lines.iter().map(Line::count).sum()
 }
 
-pub fn process_part2(input: &str) -> u64 {
+pub fn process_part2(input: &str) -> BigUint {
     let lines: Vec<Line> = input
         .lines()
         .flat_map(Line::from_str)
-        .map(|l| l.expand())
+        .map(|l| l.expand(5))
         .collect();
-    lines.iter().map(|l| l.dp(0, 0, &mut HashMap::new())).sum()
+    lines.iter().map(Line::count).sum()
+}
+
+/// Total arrangements with every record unfolded `n` times.
+pub fn process_unfolded(input: &str, n: usize) -> BigUint {
+    input
+        .lines()
+        .flat_map(Line::from_str)
+        .map(|l| l.count_unfolded(n))
+        .sum()
 }
 
 #[cfg(test)]
@@ -126,7 +293,7 @@ mod tests {
 ????.#...#... 4,1,1
 ????.######..#####. 1,6,5
 ?###???????? 3,2,1";
-        assert_eq!(21_u64, process_part1(input));
+        assert_eq!(BigUint::from(21_u64), process_part1(input));
     }
 
     #[test]
@@ -135,7 +302,7 @@ mod tests {
         println!("{:?}", Line::from_str(input).unwrap().springs);
         println!("{:?}", Line::from_str(input).unwrap().backup);
         assert_eq!(
-            10_u64,
+            BigUint::from(10_u64),
             Line::from_str(input).unwrap().dp(0, 0, &mut HashMap::new())
         );
     }
@@ -148,6 +315,46 @@ mod tests {
 ????.#...#... 4,1,1
 ????.######..#####. 1,6,5
 ?###???????? 3,2,1";
-        assert_eq!(525152_u64, process_part2(input));
+        assert_eq!(BigUint::from(525152_u64), process_part2(input));
+        assert_eq!(BigUint::from(525152_u64), process_unfolded(input, 5));
+    }
+
+    #[test]
+    fn test_arrangements() {
+        let line = Line::from_str("?###???????? 3,2,1").unwrap();
+        let arrangements: Vec<String> = line.arrangements().collect();
+        assert_eq!(arrangements.len(), 10);
+        assert_eq!(arrangements[0], ".###....##.#");
+        assert_eq!(arrangements[9], ".###.##.#...");
+        for (i, a) in arrangements.iter().enumerate() {
+            assert_eq!(line.arrangement(&BigUint::from(i)).as_ref(), Some(a));
+        }
+        assert_eq!(line.arrangement(&BigUint::from(10_u64)), None);
+
+        let line = Line::from_str("???.### 1,1,3").unwrap();
+        assert_eq!(line.arrangements().collect::<Vec<_>>(), vec!["#.#.###"]);
+        let line = Line::from_str("#.# 3").unwrap();
+        assert_eq!(line.arrangements().count(), 0);
+    }
+
+    #[test]
+    fn test_sample() {
+        let line = Line::from_str(".??..??...?##. 1,1,3").unwrap();
+        let all: Vec<String> = line.arrangements().collect();
+        let mut rng = rand::thread_rng();
+        for _ in 0..20 {
+            assert!(all.contains(&line.sample(&mut rng).unwrap()));
+        }
+        assert_eq!(Line::from_str("# 2").unwrap().sample(&mut rng), None);
+
+        // Far more arrangements than fit in a u64
+        let line = Line::from_str("?###???????? 3,2,1").unwrap().expand(20);
+        let sampled = Line::from_str(&format!(
+            "{} {}",
+            line.sample(&mut rng).unwrap(),
+            "3,2,1,".repeat(20).trim_end_matches(',')
+        ))
+        .unwrap();
+        assert_eq!(sampled.count(), BigUint::one());
     }
 }
@@ -0,0 +1,159 @@
+use crate::{Line, State};
+
+/// A nonogram puzzle: every row and column is a line of springs with only unknown springs and
+/// the group sizes as clue.
+pub struct Nonogram {
+    rows: Vec<Vec<usize>>,
+    columns: Vec<Vec<usize>>,
+}
+
+/// Solutions of a nonogram, written with `.` and `#`. `Many` holds two different solutions.
+#[derive(Debug, PartialEq)]
+pub enum Solutions {
+    None,
+    Unique(Vec<String>),
+    Many(Vec<String>, Vec<String>),
+}
+
+type Grid = Vec<Vec<State>>;
+
+impl Nonogram {
+    pub fn new(rows: Vec<Vec<usize>>, columns: Vec<Vec<usize>>) -> Nonogram {
+        Nonogram { rows, columns }
+    }
+
+    pub fn solve(&self) -> Solutions {
+        let grid = vec![vec![State::Unknown; self.columns.len()]; self.rows.len()];
+        let mut found = Vec::new();
+        self.search(grid, &mut found);
+        let mut found = found.into_iter().map(|grid| render(&grid));
+        match (found.next(), found.next()) {
+            (None, _) => Solutions::None,
+            (Some(solution), None) => Solutions::Unique(solution),
+            (Some(first), Some(second)) => Solutions::Many(first, second),
+        }
+    }
+
+    /// Propagates and then guesses the first unknown cell, until two solutions are found.
+    fn search(&self, mut grid: Grid, found: &mut Vec<Grid>) {
+        if found.len() >= 2 || !self.propagate(&mut grid) {
+            return;
+        }
+        let unknown = grid.iter().enumerate().find_map(|(row, cells)| {
+            cells
+                .iter()
+                .position(|cell| cell == &State::Unknown)
+                .map(|column| (row, column))
+        });
+        match unknown {
+            None => found.push(grid),
+            Some((row, column)) => {
+                for guess in [State::Broken, State::Good] {
+                    let mut guessed = grid.clone();
+                    guessed[row][column] = guess;
+                    self.search(guessed, found);
+                }
+            }
+        }
+    }
+
+    /// Fixes every cell that is the same in all arrangements of its row or column, until
+    /// nothing changes. Returns false if some line has no arrangement left.
+    fn propagate(&self, grid: &mut Grid) -> bool {
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for (row, backup) in self.rows.iter().enumerate() {
+                let line = Line {
+                    springs: grid[row].clone(),
+                    backup: backup.clone(),
+                };
+                let Some(resolved) = line.resolve() else {
+                    return false;
+                };
+                for (column, state) in resolved.into_iter().enumerate() {
+                    if grid[row][column] != state {
+                        grid[row][column] = state;
+                        changed = true;
+                    }
+                }
+            }
+            for (column, backup) in self.columns.iter().enumerate() {
+                let line = Line {
+                    springs: grid.iter().map(|cells| cells[column]).collect(),
+                    backup: backup.clone(),
+                };
+                let Some(resolved) = line.resolve() else {
+                    return false;
+                };
+                for (row, state) in resolved.into_iter().enumerate() {
+                    if grid[row][column] != state {
+                        grid[row][column] = state;
+                        changed = true;
+                    }
+                }
+            }
+        }
+        true
+    }
+}
+
+fn render(grid: &Grid) -> Vec<String> {
+    grid.iter()
+        .map(|cells| {
+            cells
+                .iter()
+                .map(|cell| if cell == &State::Broken { '#' } else { '.' })
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unique() {
+        let nonogram = Nonogram::new(
+            vec![vec![1, 1], vec![3], vec![1], vec![]],
+            vec![vec![2], vec![2], vec![2]],
+        );
+        let expected = vec!["#.#", "###", ".#.", "..."];
+        assert_eq!(
+            nonogram.solve(),
+            Solutions::Unique(expected.into_iter().map(String::from).collect())
+        );
+    }
+
+    #[test]
+    fn test_many_and_none() {
+        let nonogram = Nonogram::new(vec![vec![1], vec![1]], vec![vec![1], vec![1]]);
+        assert_eq!(
+            nonogram.solve(),
+            Solutions::Many(
+                vec![String::from("#."), String::from(".#")],
+                vec![String::from(".#"), String::from("#.")]
+            )
+        );
+        let nonogram = Nonogram::new(vec![vec![2], vec![]], vec![vec![1], vec![]]);
+        assert_eq!(nonogram.solve(), Solutions::None);
+    }
+
+    #[test]
+    fn test_wide_row() {
+        // The row alone has more than u64::MAX arrangements
+        let columns = (0..100)
+            .map(|column| {
+                if column < 60 && column % 2 == 0 {
+                    vec![1]
+                } else {
+                    vec![]
+                }
+            })
+            .collect();
+        let nonogram = Nonogram::new(vec![vec![1; 30]], columns);
+        let expected = format!("{}{}", "#.".repeat(30), ".".repeat(40));
+        assert_eq!(nonogram.solve(), Solutions::Unique(vec![expected]));
+    }
+}
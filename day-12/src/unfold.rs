@@ -0,0 +1,286 @@
+use num::{BigUint, One, Zero};
+use std::collections::{HashMap, HashSet};
+
+use crate::{Line, State};
+
+/// Where an arrangement stands after some springs: the groups started so far, the broken
+/// springs the current group still needs, and whether the next spring has to be good.
+type Cursor = (usize, usize, bool);
+
+/// A cursor between two copies of the unfolded record: the started groups relative to the
+/// number of groups in the copies so far, and the flattened `needed * 2 + gap`.
+type Boundary = (isize, usize);
+
+/// How one copy moves the cursors, indexed by the started groups modulo the number of groups
+/// and the flattened cursor: the change of the relative started groups, the new flattened
+/// cursor, and the number of ways.
+struct Transfer {
+    groups: usize,
+    width: usize,
+    moves: Vec<Vec<(isize, usize, BigUint)>>,
+}
+
+impl Transfer {
+    fn moves(&self, (offset, cursor): Boundary) -> &[(isize, usize, BigUint)] {
+        let residue = offset.rem_euclid(self.groups.max(1) as isize) as usize;
+        &self.moves[residue * self.width + cursor]
+    }
+
+    fn drifts(&self) -> impl Iterator<Item = isize> + Clone + '_ {
+        self.moves.iter().flatten().map(|(drift, _, _)| *drift)
+    }
+}
+
+impl Line {
+    /// Number of arrangements of the record unfolded `n` times, without building the unfolded
+    /// row.
+    ///
+    /// Every copy after the first is `?` followed by the springs, and acts on a boundary cursor
+    /// in a way that only depends on the started groups modulo the number of groups. When the
+    /// boundaries reachable from the first copy are finitely many, the copies are a matrix over
+    /// them and its `n - 1`th power is taken by squaring. Otherwise arrangements can drift
+    /// arbitrarily far from one copy of the groups per copy of the springs, as in `?? 1`, and
+    /// the copies are applied one by one to the boundaries that can still get back.
+    pub fn count_unfolded(&self, n: usize) -> BigUint {
+        if n == 0 {
+            return BigUint::one();
+        }
+        let transfer = self.transfer();
+        let first: HashMap<Boundary, BigUint> = self
+            .advance(&self.springs, (0, 0, false))
+            .into_iter()
+            .map(|((started, needed, gap), ways)| {
+                let offset = started as isize - self.backup.len() as isize;
+                ((offset, needed * 2 + gap as usize), ways)
+            })
+            .collect();
+        let Some(boundaries) = self.boundaries(&first, &transfer) else {
+            return self.sweep(&first, &transfer, n);
+        };
+
+        let index: HashMap<Boundary, usize> = boundaries
+            .iter()
+            .enumerate()
+            .map(|(i, boundary)| (*boundary, i))
+            .collect();
+        let mut power = vec![vec![BigUint::zero(); boundaries.len()]; boundaries.len()];
+        for (i, &(offset, cursor)) in boundaries.iter().enumerate() {
+            for (drift, to, ways) in transfer.moves((offset, cursor)) {
+                power[i][index[&(offset + drift, *to)]] += ways;
+            }
+        }
+        let mut counts = vec![vec![BigUint::zero(); boundaries.len()]];
+        for (boundary, count) in first {
+            counts[0][index[&boundary]] = count;
+        }
+        let mut copies = n - 1;
+        while copies > 0 {
+            if copies % 2 == 1 {
+                counts = multiply(&counts, &power);
+            }
+            copies /= 2;
+            if copies > 0 {
+                power = multiply(&power, &power);
+            }
+        }
+        boundaries
+            .iter()
+            .zip(&counts[0])
+            .filter(|((offset, cursor), _)| *offset == 0 && cursor / 2 == 0)
+            .map(|(_, count)| count)
+            .sum()
+    }
+
+    /// The transfer of a copy for every cursor.
+    fn transfer(&self) -> Transfer {
+        let groups = self.backup.len();
+        let width = (self.backup.iter().copied().max().unwrap_or(0) + 1) * 2;
+        let mut unit = vec![State::Unknown];
+        unit.extend(&self.springs);
+        let mut moves = Vec::new();
+        for residue in 0..groups.max(1) {
+            for needed in 0..width / 2 {
+                for gap in [false, true] {
+                    moves.push(
+                        self.advance(&unit, (residue, needed, gap))
+                            .into_iter()
+                            .map(|((started, needed, gap), ways)| {
+                                let drift = (started - residue) as isize - groups as isize;
+                                (drift, needed * 2 + gap as usize, ways)
+                            })
+                            .collect(),
+                    );
+                }
+            }
+        }
+        Transfer {
+            groups,
+            width,
+            moves,
+        }
+    }
+
+    /// Every boundary reachable from `first`, or `None` if there is no bound on them.
+    ///
+    /// Without a cycle of copies that changes the offset, no path repeats a cursor modulo the
+    /// groups, which bounds the offset.
+    fn boundaries(
+        &self,
+        first: &HashMap<Boundary, BigUint>,
+        transfer: &Transfer,
+    ) -> Option<Vec<Boundary>> {
+        let largest_drift = transfer.drifts().map(isize::abs).max().unwrap_or(0);
+        let start = first.keys().map(|(offset, _)| offset.abs()).max();
+        let bound = start.unwrap_or(0) + transfer.moves.len() as isize * largest_drift;
+
+        let mut boundaries: Vec<Boundary> = first.keys().copied().collect();
+        let mut seen: HashSet<Boundary> = boundaries.iter().copied().collect();
+        let mut i = 0;
+        while let Some(&(offset, cursor)) = boundaries.get(i) {
+            for (drift, to, _) in transfer.moves((offset, cursor)) {
+                let to = (offset + drift, *to);
+                if to.0.abs() > bound {
+                    return None;
+                }
+                if seen.insert(to) {
+                    boundaries.push(to);
+                }
+            }
+            i += 1;
+        }
+        Some(boundaries)
+    }
+
+    /// Applies the remaining `n - 1` copies one at a time. The counts of each copy are kept
+    /// for the offsets that are reachable and from which the remaining copies can still get
+    /// back to zero.
+    fn sweep(&self, first: &HashMap<Boundary, BigUint>, transfer: &Transfer, n: usize) -> BigUint {
+        let width = transfer.width;
+        let drifts = transfer.drifts();
+        let (lowest, highest) = (drifts.clone().min().unwrap_or(0), drifts.max().unwrap_or(0));
+        let offsets = first.keys().map(|(offset, _)| *offset);
+        let (mut low, mut high) = (
+            offsets.clone().min().unwrap_or(0),
+            offsets.max().unwrap_or(0),
+        );
+        let mut counts = vec![BigUint::zero(); (high - low + 1) as usize * width];
+        for ((offset, cursor), count) in first {
+            counts[(offset - low) as usize * width + cursor] = count.clone();
+        }
+        for copy in 1..n {
+            let remaining = (n - copy - 1) as isize;
+            let next_low = (low + lowest).max(-remaining * highest);
+            let next_high = (high + highest).min(-remaining * lowest);
+            if next_low > next_high {
+                return BigUint::zero();
+            }
+            let mut next = vec![BigUint::zero(); (next_high - next_low + 1) as usize * width];
+            for (i, count) in counts.iter().enumerate() {
+                if count.is_zero() {
+                    continue;
+                }
+                let (offset, cursor) = (low + (i / width) as isize, i % width);
+                for (drift, to, ways) in transfer.moves((offset, cursor)) {
+                    let to_offset = offset + drift;
+                    if (next_low..=next_high).contains(&to_offset) {
+                        next[(to_offset - next_low) as usize * width + to] += count * ways;
+                    }
+                }
+            }
+            (counts, low, high) = (next, next_low, next_high);
+        }
+        if !(low..=high).contains(&0) {
+            return BigUint::zero();
+        }
+        // The cursors that need no more broken springs
+        counts[(-low) as usize * width..][..2].iter().sum()
+    }
+
+    /// Counts the cursors reached from `start` for every way to fill in `springs`.
+    fn advance(&self, springs: &[State], start: Cursor) -> HashMap<Cursor, BigUint> {
+        let mut cursors = HashMap::from([(start, BigUint::one())]);
+        for spring in springs {
+            let mut next: HashMap<Cursor, BigUint> = HashMap::new();
+            let mut add = |cursor: Cursor, count: &BigUint| {
+                *next.entry(cursor).or_insert_with(BigUint::zero) += count;
+            };
+            for ((started, needed, gap), count) in &cursors {
+                if *needed > 0 {
+                    if spring != &State::Good {
+                        add((*started, needed - 1, *needed == 1), count);
+                    }
+                } else if *gap {
+                    if spring != &State::Broken {
+                        add((*started, 0, false), count);
+                    }
+                } else {
+                    if spring != &State::Broken {
+                        add((*started, 0, false), count);
+                    }
+                    if spring != &State::Good && !self.backup.is_empty() {
+                        let len = self.backup[started % self.backup.len()];
+                        add((started + 1, len - 1, len == 1), count);
+                    }
+                }
+            }
+            cursors = next;
+        }
+        cursors
+    }
+}
+
+/// Product of two matrices of counts.
+fn multiply(a: &[Vec<BigUint>], b: &[Vec<BigUint>]) -> Vec<Vec<BigUint>> {
+    a.iter()
+        .map(|row| {
+            let mut product = vec![BigUint::zero(); b[0].len()];
+            for (count, b_row) in row.iter().zip(b) {
+                if count.is_zero() {
+                    continue;
+                }
+                for (p, ways) in product.iter_mut().zip(b_row) {
+                    if !ways.is_zero() {
+                        *p += count * ways;
+                    }
+                }
+            }
+            product
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn matches_expanded_dp() {
+        let input = "???.### 1,1,3
+.??..??...?##. 1,1,3
+?#?#?#?#?#?#?#? 1,3,1,6
+????.#...#... 4,1,1
+????.######..#####. 1,6,5
+?###???????? 3,2,1";
+        for line in input.lines().flat_map(Line::from_str) {
+            for n in 0..6 {
+                assert_eq!(line.count_unfolded(n), line.expand(n).count());
+            }
+        }
+    }
+
+    #[test]
+    fn test_large_unfold() {
+        let line = Line::from_str("???.### 1,1,3").unwrap();
+        assert_eq!(line.count_unfolded(2000), BigUint::one());
+        assert_eq!(line.count_unfolded(1_000_000_000), BigUint::one());
+        let line = Line::from_str("?? 1").unwrap();
+        assert_eq!(line.count_unfolded(40), line.expand(40).count());
+        assert!(
+            Line::from_str("?###???????? 3,2,1")
+                .unwrap()
+                .count_unfolded(1000)
+                > BigUint::from(u128::MAX)
+        );
+    }
+}
@@ -1,5 +1,7 @@
 use std::str::FromStr;
 
+pub mod symmetry;
+
 #[derive(PartialEq, Copy, Clone, Debug)]
 enum Item {
     Ash,
@@ -7,8 +9,63 @@ enum Item {
 }
 
 #[derive(Debug)]
-struct Field {
+pub struct Field {
     pattern: Vec<Vec<Item>>,
+    rows: Vec<Bits>,
+    columns: Vec<Bits>,
+}
+
+/// A row or column with one bit per cell, set for rock.
+#[derive(PartialEq, Debug)]
+struct Bits(Vec<u64>);
+
+impl Bits {
+    fn new<'a>(items: impl Iterator<Item = &'a Item>) -> Bits {
+        let mut words = Vec::new();
+        for (i, item) in items.enumerate() {
+            if i % 64 == 0 {
+                words.push(0);
+            }
+            if item == &Item::Rock {
+                *words.last_mut().unwrap() |= 1 << (i % 64);
+            }
+        }
+        Bits(words)
+    }
+
+    fn count_not_same(&self, other: &Bits) -> usize {
+        self.0
+            .iter()
+            .zip(other.0.iter())
+            .map(|(w1, w2)| (w1 ^ w2).count_ones() as usize)
+            .sum()
+    }
+}
+
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum Axis {
+    /// A line between two rows.
+    Horizontal,
+    /// A line between two columns.
+    Vertical,
+}
+
+/// A mirror line after `position` rows or columns, with the number of cells that differ from
+/// their reflection.
+#[derive(PartialEq, Debug)]
+pub struct Reflection {
+    pub axis: Axis,
+    pub position: usize,
+    pub smudges: usize,
+}
+
+impl Reflection {
+    fn value(&self) -> u64 {
+        match self.axis {
+            Axis::Horizontal => (self.position * 100) as u64,
+            Axis::Vertical => self.position as u64,
+        }
+    }
 }
 
 impl FromStr for Field {
@@ -27,42 +84,46 @@ impl FromStr for Field {
                     .collect()
             })
             .collect();
-        Ok(Field { pattern })
+        Ok(Field::new(pattern))
     }
 }
 
 impl Field {
-    fn find_hori_mirror(&self) -> Option<usize> {
-        (0..self.pattern.len() - 1)
-            .find(|i| {
-                let space_to_right = self.pattern.len() - i - 2;
-                let check_range: &usize = i.min(&space_to_right);
-                (0..=*check_range).all(|j| {
-                    self.pattern.get(i - j).unwrap() == self.pattern.get(i + j + 1).unwrap()
-                })
-            })
-            .map(|u| u + 1)
-    }
-
-    fn find_near_miss_hor_mirror(&self) -> Option<usize> {
-        (0..self.pattern.len() - 1)
-            .find(|i| {
-                let space_to_right = self.pattern.len() - i - 2;
-                let check_range: &usize = i.min(&space_to_right);
-                let s = (0..=*check_range)
-                    .map(|j| {
-                        count_not_same(
-                            self.pattern.get(i - j).unwrap(),
-                            self.pattern.get(i + j + 1).unwrap(),
-                        )
-                    })
-                    .sum::<usize>();
-                s == 1
-            })
-            .map(|u| u + 1)
+    fn new(pattern: Vec<Vec<Item>>) -> Field {
+        let rows = pattern.iter().map(|row| Bits::new(row.iter())).collect();
+        let columns = (0..pattern.first().map_or(0, Vec::len))
+            .map(|col| Bits::new(pattern.iter().map(|row| &row[col])))
+            .collect();
+        Field {
+            pattern,
+            rows,
+            columns,
+        }
     }
 
-    fn transpose(&self) -> Field {
+    /// All horizontal and then all vertical mirror lines with at most `max_smudges` differing
+    /// cells, each sorted by position.
+    pub fn reflections(&self, max_smudges: usize) -> Vec<Reflection> {
+        let horizontal = mirrors(&self.rows, max_smudges)
+            .into_iter()
+            .map(|(position, smudges)| Reflection {
+                axis: Axis::Horizontal,
+                position,
+                smudges,
+            });
+        let vertical =
+            mirrors(&self.columns, max_smudges)
+                .into_iter()
+                .map(|(position, smudges)| Reflection {
+                    axis: Axis::Vertical,
+                    position,
+                    smudges,
+                });
+        horizontal.chain(vertical).collect()
+    }
+
+    /// Swaps rows and columns.
+    pub fn transpose(&self) -> Field {
         let pattern = (0..self.pattern.first().unwrap().len())
             .map(|col| {
                 (0..self.pattern.len())
@@ -70,31 +131,43 @@ impl Field {
                     .collect()
             })
             .collect();
-        Field { pattern }
+        Field::new(pattern)
+    }
+
+    /// Value of the first mirror line with exactly `smudges` differing cells.
+    fn get_value_with_smudges(&self, smudges: usize) -> u64 {
+        self.reflections(smudges)
+            .iter()
+            .find(|r| r.smudges == smudges)
+            .map(Reflection::value)
+            .unwrap_or(0)
     }
 
     fn get_value(&self) -> u64 {
-        if let Some(hor) = self.find_hori_mirror() {
-            (hor * 100) as u64
-        } else {
-            self.transpose().find_hori_mirror().unwrap_or(0) as u64
-        }
+        self.get_value_with_smudges(0)
     }
 
     fn get_value_near_miss(&self) -> u64 {
-        if let Some(hor) = self.find_near_miss_hor_mirror() {
-            (hor * 100) as u64
-        } else {
-            self.transpose().find_near_miss_hor_mirror().unwrap() as u64
-        }
+        self.get_value_with_smudges(1)
     }
 }
 
-fn count_not_same<T>(v1: &Vec<T>, v2: &Vec<T>) -> usize
-where
-    T: PartialEq,
-{
-    v1.iter().zip(v2.iter()).filter(|(i1, i2)| i1 != i2).count()
+/// Mirror lines between `lines` with at most `max_smudges` differing cells.
+fn mirrors(lines: &[Bits], max_smudges: usize) -> Vec<(usize, usize)> {
+    (0..lines.len().saturating_sub(1))
+        .filter_map(|i| {
+            let space_to_right = lines.len() - i - 2;
+            let check_range = i.min(space_to_right);
+            let mut smudges = 0;
+            for j in 0..=check_range {
+                smudges += lines[i - j].count_not_same(&lines[i + j + 1]);
+                if smudges > max_smudges {
+                    return None;
+                }
+            }
+            Some((i + 1, smudges))
+        })
+        .collect()
 }
 
 pub fn process_part1(input: &str) -> u64 {
@@ -142,6 +215,52 @@ mod tests {
         let f = Field::from_str(".#\n..").unwrap();
         let ft = Field::from_str("..\n#.").unwrap();
         assert_eq!(f.transpose().pattern, ft.pattern);
+        assert_eq!(f.transpose().rows, f.columns);
+    }
+
+    #[test]
+    fn test_reflections() {
+        let f = Field::from_str(
+            "#.##..##.
+..#.##.#.
+##......#
+##......#
+..#.##.#.
+..##..##.
+#.#.##.#.",
+        )
+        .unwrap();
+        let vertical = Reflection {
+            axis: Axis::Vertical,
+            position: 5,
+            smudges: 0,
+        };
+        assert_eq!(f.reflections(0), vec![vertical]);
+        let r = f.reflections(1);
+        assert_eq!(
+            r[0],
+            Reflection {
+                axis: Axis::Horizontal,
+                position: 3,
+                smudges: 1
+            }
+        );
+        assert_eq!(r.len(), 2);
+
+        let f = Field::from_str("##\n##").unwrap();
+        assert_eq!(f.reflections(0).len(), 2);
+        assert_eq!(f.reflections(2).len(), 2);
+    }
+
+    #[test]
+    fn wide_rows() {
+        let row = ".#".repeat(35);
+        let mut smudged = row.clone();
+        smudged.replace_range(65..66, ".");
+        let f = Field::from_str(&format!("{row}\n{smudged}")).unwrap();
+        assert_eq!(f.rows[0].0.len(), 2);
+        assert_eq!(f.rows[0].count_not_same(&f.rows[1]), 1);
+        assert_eq!(f.get_value_near_miss(), 100);
     }
 
     #[test]
@@ -0,0 +1,233 @@
+use crate::{Axis, Field};
+
+/// A way for a pattern, or a part of it, to map onto itself.
+#[derive(PartialEq, Debug)]
+pub enum Symmetry {
+    /// Mirror line after `position` rows or columns, as in `Field::reflections`.
+    Mirror { axis: Axis, position: usize },
+    /// Rotation by 180° around `centre`, given as `(row, column)` in half cells, so that
+    /// `(2, 3)` is the middle of row 1 between columns 1 and 2. The overlap of the pattern
+    /// with its rotation is compared.
+    Rotation { centre: (usize, usize) },
+    /// Mirror along the diagonal of the square of `size` cells with its top left cell at
+    /// `corner`. The diagonal runs from top left to bottom right, or from top right to
+    /// bottom left if `anti`.
+    Diagonal {
+        corner: (usize, usize),
+        size: usize,
+        anti: bool,
+    },
+}
+
+/// A symmetry with the number of cell pairs it compares and how many of them differ.
+#[derive(PartialEq, Debug)]
+pub struct Match {
+    pub symmetry: Symmetry,
+    pub pairs: usize,
+    pub differing: usize,
+}
+
+impl Match {
+    /// Share of the compared pairs that agree, from 0 to 1.
+    pub fn score(&self) -> f64 {
+        1.0 - self.differing as f64 / self.pairs as f64
+    }
+}
+
+pub struct Options {
+    /// Largest share of differing pairs that still counts as symmetric.
+    pub tolerance: f64,
+    /// Smallest square for diagonal mirrors, and smallest overlap in each direction for
+    /// rotations.
+    pub min_size: usize,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            tolerance: 0.0,
+            min_size: 2,
+        }
+    }
+}
+
+impl Field {
+    /// Mirror lines, rotations and diagonal mirrors with at most a share of `tolerance`
+    /// differing pairs, in that order.
+    pub fn symmetries(&self, options: &Options) -> Vec<Match> {
+        if self.rows.is_empty() || self.columns.is_empty() {
+            return Vec::new();
+        }
+        let mut matches = self.mirrors();
+        matches.extend(self.rotations(options.min_size));
+        matches.extend(self.diagonals(options.min_size));
+        matches.retain(|m| m.pairs > 0 && m.differing as f64 <= options.tolerance * m.pairs as f64);
+        matches
+    }
+
+    fn mirrors(&self) -> Vec<Match> {
+        let (rows, columns) = (self.pattern.len(), self.pattern[0].len());
+        self.reflections(usize::MAX)
+            .into_iter()
+            .map(|r| {
+                let (len, across) = match r.axis {
+                    Axis::Horizontal => (rows, columns),
+                    Axis::Vertical => (columns, rows),
+                };
+                let depth = r.position.min(len - r.position);
+                Match {
+                    symmetry: Symmetry::Mirror {
+                        axis: r.axis,
+                        position: r.position,
+                    },
+                    pairs: depth * across,
+                    differing: r.smudges,
+                }
+            })
+            .collect()
+    }
+
+    fn rotations(&self, min_size: usize) -> Vec<Match> {
+        let (rows, columns) = (self.pattern.len(), self.pattern[0].len());
+        let mut matches = Vec::new();
+        for row2 in 0..2 * rows - 1 {
+            for column2 in 0..2 * columns - 1 {
+                // Cells whose rotation is inside the pattern
+                let row_range = row2.saturating_sub(rows - 1)..=row2.min(rows - 1);
+                let column_range = column2.saturating_sub(columns - 1)..=column2.min(columns - 1);
+                if row_range.clone().count() < min_size || column_range.clone().count() < min_size {
+                    continue;
+                }
+                let cells = row_range.flat_map(|row| column_range.clone().map(move |c| (row, c)));
+                // Every pair once, from the cell that comes first
+                let pairs =
+                    cells.filter(|&(row, column)| (row, column) < (row2 - row, column2 - column));
+                matches.push(self.compare(
+                    Symmetry::Rotation {
+                        centre: (row2, column2),
+                    },
+                    pairs.map(|(row, column)| ((row, column), (row2 - row, column2 - column))),
+                ));
+            }
+        }
+        matches
+    }
+
+    fn diagonals(&self, min_size: usize) -> Vec<Match> {
+        let (rows, columns) = (self.pattern.len(), self.pattern[0].len());
+        let mut matches = Vec::new();
+        for size in min_size.max(2)..=rows.min(columns) {
+            for row in 0..=rows - size {
+                for column in 0..=columns - size {
+                    for anti in [false, true] {
+                        let pairs = (0..size).flat_map(|i| {
+                            (0..size).filter_map(move |j| {
+                                if anti && i + j < size - 1 {
+                                    Some(((i, j), (size - 1 - j, size - 1 - i)))
+                                } else if !anti && i < j {
+                                    Some(((i, j), (j, i)))
+                                } else {
+                                    None
+                                }
+                            })
+                        });
+                        matches.push(self.compare(
+                            Symmetry::Diagonal {
+                                corner: (row, column),
+                                size,
+                                anti,
+                            },
+                            pairs.map(|((i1, j1), (i2, j2))| {
+                                ((row + i1, column + j1), (row + i2, column + j2))
+                            }),
+                        ));
+                    }
+                }
+            }
+        }
+        matches
+    }
+
+    fn compare(
+        &self,
+        symmetry: Symmetry,
+        pairs: impl Iterator<Item = ((usize, usize), (usize, usize))>,
+    ) -> Match {
+        let (mut count, mut differing) = (0, 0);
+        for ((row1, column1), (row2, column2)) in pairs {
+            count += 1;
+            if self.pattern[row1][column1] != self.pattern[row2][column2] {
+                differing += 1;
+            }
+        }
+        Match {
+            symmetry,
+            pairs: count,
+            differing,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_rotation_and_diagonals() {
+        let f = Field::from_str("#..\n.#.\n..#").unwrap();
+        let exact = f.symmetries(&Options::default());
+        assert!(exact.contains(&Match {
+            symmetry: Symmetry::Rotation { centre: (2, 2) },
+            pairs: 4,
+            differing: 0
+        }));
+        assert!(exact.contains(&Match {
+            symmetry: Symmetry::Diagonal {
+                corner: (0, 0),
+                size: 3,
+                anti: false
+            },
+            pairs: 3,
+            differing: 0
+        }));
+        assert!(exact.contains(&Match {
+            symmetry: Symmetry::Diagonal {
+                corner: (0, 0),
+                size: 3,
+                anti: true
+            },
+            pairs: 3,
+            differing: 0
+        }));
+        assert!(!exact
+            .iter()
+            .any(|m| matches!(m.symmetry, Symmetry::Mirror { .. })));
+    }
+
+    #[test]
+    fn test_tolerance() {
+        let f = Field::from_str("##.\n.#.\n..#").unwrap();
+        let options = Options {
+            tolerance: 0.0,
+            min_size: 3,
+        };
+        let rotation = |m: &Match| m.symmetry == Symmetry::Rotation { centre: (2, 2) };
+        assert!(!f.symmetries(&options).iter().any(rotation));
+        let options = Options {
+            tolerance: 0.25,
+            min_size: 3,
+        };
+        let partial = f.symmetries(&options);
+        let found = partial.iter().find(|m| rotation(m)).unwrap();
+        assert_eq!(found.score(), 0.75);
+    }
+
+    #[test]
+    fn test_empty() {
+        let f = Field::from_str("").unwrap();
+        assert_eq!(f.symmetries(&Options::default()), Vec::new());
+        let f = Field::from_str("\n\n").unwrap();
+        assert_eq!(f.symmetries(&Options::default()), Vec::new());
+    }
+}
@@ -0,0 +1,126 @@
+/// Where a sequence of states starts to repeat: after `prefix` steps the states come back every
+/// `period` steps.
+#[derive(PartialEq, Debug)]
+pub struct Cycle {
+    pub prefix: usize,
+    pub period: usize,
+}
+
+/// Finds the cycle of the states `start`, `step(start)`, ... with Brent's algorithm, which
+/// only keeps two states around. The states must repeat at some point.
+pub fn find_cycle<T: PartialEq + Clone>(start: &T, step: impl Fn(&T) -> T) -> Cycle {
+    // Find the period by moving the tortoise to the hare at every power of two
+    let (mut power, mut period) = (1, 1);
+    let mut tortoise = start.clone();
+    let mut hare = step(start);
+    while tortoise != hare {
+        if power == period {
+            tortoise = hare.clone();
+            power *= 2;
+            period = 0;
+        }
+        hare = step(&hare);
+        period += 1;
+    }
+
+    // With the hare one period ahead, both meet at the start of the cycle
+    let mut tortoise = start.clone();
+    let mut hare = start.clone();
+    for _ in 0..period {
+        hare = step(&hare);
+    }
+    let mut prefix = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        prefix += 1;
+    }
+    Cycle { prefix, period }
+}
+
+/// The state after `n` steps, skipping over whole periods of the cycle. Runs the first phase
+/// of `find_cycle` and returns as soon as it gets to the `n`th state, so that small `n` only
+/// take `n` steps.
+pub fn nth_iterate<T: PartialEq + Clone>(start: T, step: impl Fn(&T) -> T, n: usize) -> T {
+    if n == 0 {
+        return start;
+    }
+    let (mut power, mut period) = (1, 1);
+    let mut tortoise = start.clone();
+    let mut hare = step(&start);
+    let mut walked = 1;
+    while tortoise != hare {
+        if walked == n {
+            return hare;
+        }
+        if power == period {
+            tortoise = hare.clone();
+            power *= 2;
+            period = 0;
+        }
+        hare = step(&hare);
+        period += 1;
+        walked += 1;
+    }
+    // The hare is a whole period past the tortoise, so the states repeat from here on
+    (0..(n - walked) % period).fold(hare, |state, _| step(&state))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    #[test]
+    fn test_find_cycle() {
+        // 3, 10, 5, 16, 8, 4, 2, 1, 4, 2, 1, ...
+        let collatz = |n: &u64| {
+            if n.is_multiple_of(2) {
+                n / 2
+            } else {
+                3 * n + 1
+            }
+        };
+        assert_eq!(
+            find_cycle(&3, collatz),
+            Cycle {
+                prefix: 5,
+                period: 3
+            }
+        );
+        assert_eq!(
+            find_cycle(&0, |n| (n + 1) % 7),
+            Cycle {
+                prefix: 0,
+                period: 7
+            }
+        );
+    }
+
+    #[test]
+    fn test_nth_iterate() {
+        let collatz = |n: &u64| {
+            if n.is_multiple_of(2) {
+                n / 2
+            } else {
+                3 * n + 1
+            }
+        };
+        assert_eq!(nth_iterate(3, collatz, 2), 5);
+        assert_eq!(nth_iterate(3, collatz, 1_000_000_000), 1);
+        assert_eq!(nth_iterate(3, collatz, 1_000_000_001), 4);
+        assert_eq!(nth_iterate(3, collatz, 0), 3);
+    }
+
+    #[test]
+    fn test_nth_iterate_stops_early() {
+        let steps = Cell::new(0);
+        let step = |n: &u64| {
+            steps.set(steps.get() + 1);
+            (n + 1) % 1_000_000
+        };
+        assert_eq!(nth_iterate(0, step, 3), 3);
+        assert_eq!(steps.get(), 3);
+        assert_eq!(nth_iterate(0, step, 1_000_000_003), 3);
+    }
+}
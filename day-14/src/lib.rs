@@ -1,4 +1,6 @@
-use std::{collections::HashMap, str::FromStr, u64};
+use std::str::FromStr;
+
+pub mod cycle;
 
 #[derive(PartialEq, Copy, Clone, Debug, Hash, Eq)]
 enum Item {
@@ -7,13 +9,42 @@ enum Item {
     Empty,
 }
 
-enum Direction {
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum Direction {
     Up,
     Right,
     Down,
     Left,
 }
 
+impl Direction {
+    /// Compass letter, with north up.
+    fn from_char(c: &char) -> Option<Direction> {
+        match c {
+            'N' => Some(Direction::Up),
+            'E' => Some(Direction::Right),
+            'S' => Some(Direction::Down),
+            'W' => Some(Direction::Left),
+            _ => None,
+        }
+    }
+}
+
+/// Tilts to apply one after another, written as compass letters like `NWSE`.
+#[derive(PartialEq, Debug)]
+pub struct Program(Vec<Direction>);
+
+impl FromStr for Program {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.chars()
+            .map(|c| Direction::from_char(&c).ok_or(format!("no direction {c:?}")))
+            .collect::<Result<_, _>>()
+            .map(Program)
+    }
+}
+
 impl Item {
     fn from_char(c: &char) -> Item {
         match c {
@@ -25,38 +56,102 @@ impl Item {
     }
 }
 
+/// The platform as bitboards, one bitset per row with bit `i % 64` of word `i / 64` for
+/// column `i`.
 #[derive(Debug, Hash, PartialEq, Eq, Clone)]
-struct Field {
-    field: Vec<Vec<Item>>,
+pub struct Field {
+    width: usize,
+    round: Vec<Vec<u64>>,
+    rocks: Vec<Vec<u64>>,
 }
 
 impl FromStr for Field {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let field: Vec<Vec<Item>> = s
-            .lines()
-            .map(|l| l.chars().map(|c| Item::from_char(&c)).collect())
-            .collect();
-        Ok(Field { field })
+        let width = s.lines().next().map_or(0, |l| l.chars().count());
+        let words = width.div_ceil(64);
+        let (mut round, mut rocks) = (Vec::new(), Vec::new());
+        for (row, line) in s.lines().enumerate() {
+            let (mut round_row, mut rocks_row) = (vec![0; words], vec![0; words]);
+            let mut len = 0;
+            for (i, c) in line.chars().enumerate() {
+                len += 1;
+                if i >= width {
+                    continue;
+                }
+                match Item::from_char(&c) {
+                    Item::Round => round_row[i / 64] |= 1 << (i % 64),
+                    Item::Rock => rocks_row[i / 64] |= 1 << (i % 64),
+                    Item::Empty => (),
+                }
+            }
+            if len != width {
+                return Err(format!(
+                    "row {} has {} cells instead of {}",
+                    row + 1,
+                    len,
+                    width
+                ));
+            }
+            round.push(round_row);
+            rocks.push(rocks_row);
+        }
+        Ok(Field {
+            width,
+            round,
+            rocks,
+        })
     }
 }
 
 impl Field {
-    fn transpose(&self) -> Vec<Vec<Item>> {
-        transpose(&self.field)
+    fn push(&self, dir: Direction) -> Field {
+        let mut field = self.clone();
+        field.tilt(dir);
+        field
     }
 
-    fn push(&self, dir: Direction) -> Field {
-        Field {
-            field: match dir {
-                Direction::Up => transpose(&self.transpose().iter().map(push_row_left).collect()),
-                Direction::Right => self.field.iter().map(push_row_right).collect(),
-                Direction::Down => {
-                    transpose(&self.transpose().iter().map(push_row_right).collect())
+    /// Rolls all round rocks as far as they go, in place.
+    fn tilt(&mut self, dir: Direction) {
+        match dir {
+            Direction::Up => {
+                for row in 1..self.round.len() {
+                    self.roll_vertical(row, (0..row).rev());
                 }
-                Direction::Left => self.field.iter().map(push_row_left).collect(),
-            },
+            }
+            Direction::Right => {
+                for (round, rocks) in self.round.iter_mut().zip(&self.rocks) {
+                    roll_row_right(round, rocks, self.width);
+                }
+            }
+            Direction::Down => {
+                for row in (0..self.round.len().saturating_sub(1)).rev() {
+                    self.roll_vertical(row, row + 1..self.round.len());
+                }
+            }
+            Direction::Left => {
+                for (round, rocks) in self.round.iter_mut().zip(&self.rocks) {
+                    roll_row_left(round, rocks, self.width);
+                }
+            }
+        }
+    }
+
+    /// Moves the round rocks of `row` through the rows of `path` for as long as they are free.
+    /// The rows of `path` have to be settled already.
+    fn roll_vertical(&mut self, row: usize, path: impl Iterator<Item = usize> + Clone) {
+        for word in 0..self.round[row].len() {
+            let (mut from, mut moving) = (row, self.round[row][word]);
+            for to in path.clone() {
+                moving &= !(self.round[to][word] | self.rocks[to][word]);
+                if moving == 0 {
+                    break;
+                }
+                self.round[from][word] &= !moving;
+                self.round[to][word] |= moving;
+                from = to;
+            }
         }
     }
 
@@ -66,82 +161,122 @@ impl Field {
             .push(Direction::Down)
             .push(Direction::Right)
     }
-    fn count(&self) -> u64 {
-        self.field
-            .iter()
-            .rev()
-            .enumerate()
-            .map(|(i, row)| row.iter().filter(|item| item == &&Item::Round).count() * (i + 1))
-            .sum::<usize>() as u64
-    }
-}
 
-fn push_row_left(row: &Vec<Item>) -> Vec<Item> {
-    let mut new_row = vec![Item::Empty; row.len()];
-    let mut last_free_space = 0;
-    row.iter().enumerate().for_each(|(i, item)| match item {
-        Item::Empty => (),
-        Item::Rock => {
-            new_row[i] = Item::Rock;
-            last_free_space = i + 1;
+    fn run_once(&self, program: &Program) -> Field {
+        let mut field = self.clone();
+        for dir in &program.0 {
+            field.tilt(*dir);
         }
-        Item::Round => {
-            new_row[last_free_space] = Item::Round;
-            last_free_space += 1;
+        field
+    }
+
+    /// Runs `program` `times` times, skipping ahead once the platform repeats.
+    pub fn run(&self, program: &Program, times: usize) -> Field {
+        cycle::nth_iterate(self.clone(), |field| field.run_once(program), times)
+    }
+
+    /// Load on the support beam at the `side` edge: every round rock weighs its distance in
+    /// cells from the opposite edge.
+    pub fn load(&self, side: Direction) -> u64 {
+        self.load_distribution(side).iter().sum()
+    }
+
+    /// Load on the support beam at the `side` edge split up by column for the north and south
+    /// beams, and by row for the east and west beams.
+    pub fn load_distribution(&self, side: Direction) -> Vec<u64> {
+        let height = self.round.len();
+        let bit = |row: usize, column: usize| self.round[row][column / 64] >> (column % 64) & 1;
+        match side {
+            Direction::Up | Direction::Down => (0..self.width)
+                .map(|column| {
+                    (0..height)
+                        .map(|row| {
+                            let weight = if side == Direction::Up {
+                                height - row
+                            } else {
+                                row + 1
+                            };
+                            bit(row, column) * weight as u64
+                        })
+                        .sum()
+                })
+                .collect(),
+            Direction::Left | Direction::Right => (0..height)
+                .map(|row| {
+                    (0..self.width)
+                        .map(|column| {
+                            let weight = if side == Direction::Left {
+                                self.width - column
+                            } else {
+                                column + 1
+                            };
+                            bit(row, column) * weight as u64
+                        })
+                        .sum()
+                })
+                .collect(),
         }
-    });
-    new_row
+    }
 }
 
-fn push_row_right(row: &Vec<Item>) -> Vec<Item> {
-    let mut new_row = vec![Item::Empty; row.len()];
-    let mut last_free_space = new_row.len() - 1;
-    row.iter()
-        .enumerate()
-        .rev()
-        .for_each(|(i, item)| match item {
-            Item::Empty => (),
-            Item::Rock => {
-                new_row[i] = Item::Rock;
-                last_free_space = i.saturating_sub(1);
-            }
-            Item::Round => {
-                new_row[last_free_space] = Item::Round;
-                last_free_space -= 1;
+/// Bits of word `word` that are columns of a row `width` cells wide.
+fn word_mask(word: usize, width: usize) -> u64 {
+    u64::MAX
+        .checked_shr((64 * (word + 1)).saturating_sub(width) as u32)
+        .unwrap_or(0)
+}
+
+/// Shifts every round rock that has a free cell to its left by one, until none can move.
+/// Moves across words carry the bit between neighbouring words.
+fn roll_row_left(round: &mut [u64], rocks: &[u64], width: usize) {
+    loop {
+        let (mut any, mut free_before) = (false, 0);
+        for word in 0..round.len() {
+            let free = word_mask(word, width) & !(round[word] | rocks[word]);
+            let moving = round[word] & ((free << 1) | (free_before >> 63));
+            round[word] = (round[word] & !moving) | (moving >> 1);
+            if word > 0 {
+                round[word - 1] |= moving << 63;
             }
-        });
-    new_row
+            any |= moving != 0;
+            free_before = free;
+        }
+        if !any {
+            return;
+        }
+    }
 }
 
-fn transpose(field: &Vec<Vec<Item>>) -> Vec<Vec<Item>> {
-    (0..field.first().unwrap().len())
-        .map(|col| (0..field.len()).map(|row| field[row][col]).collect())
-        .collect()
+fn roll_row_right(round: &mut [u64], rocks: &[u64], width: usize) {
+    loop {
+        let (mut any, mut free_after) = (false, 0);
+        for word in (0..round.len()).rev() {
+            let free = word_mask(word, width) & !(round[word] | rocks[word]);
+            let moving = round[word] & ((free >> 1) | (free_after << 63));
+            round[word] = (round[word] & !moving) | (moving << 1);
+            if word + 1 < round.len() {
+                round[word + 1] |= moving >> 63;
+            }
+            any |= moving != 0;
+            free_after = free;
+        }
+        if !any {
+            return;
+        }
+    }
 }
 
 pub fn process_part1(input: &str) -> u64 {
-    Field::from_str(input).unwrap().push(Direction::Up).count()
+    Field::from_str(input)
+        .unwrap()
+        .push(Direction::Up)
+        .load(Direction::Up)
 }
 
 pub fn process_part2(input: &str) -> u64 {
-    const NUM_ROTATIONS: i64 = 1_000_000_000;
-    let mut memory: HashMap<Field, i64> = HashMap::new();
-    let mut field = Field::from_str(input).unwrap();
-    let (index, i) = (0..NUM_ROTATIONS)
-        .find_map(|i| {
-            if let Some(index) = memory.insert(field.clone(), i) {
-                return Some((index, i));
-            }
-            field = field.cycle();
-            None
-        })
-        .unwrap();
-    memory
-        .iter()
-        .find(|(_, v)| **v == (NUM_ROTATIONS - index - 1) % (i - index) + index + 1)
-        .unwrap()
-        .0
-        .count()
+    const NUM_ROTATIONS: usize = 1_000_000_000;
+    let field = Field::from_str(input).unwrap();
+    cycle::nth_iterate(field, Field::cycle, NUM_ROTATIONS).load(Direction::Up)
 }
 
 #[cfg(test)]
@@ -179,17 +314,23 @@ O.#..O.#.#
     }
 
     #[test]
-    fn test_transpose_equal() {
-        let input = ".#O
-.#O
-.#O";
-        let field = Field::from_str(input).unwrap();
-        let res = vec![
-            vec![Item::Empty, Item::Empty, Item::Empty],
-            vec![Item::Rock, Item::Rock, Item::Rock],
-            vec![Item::Round, Item::Round, Item::Round],
-        ];
-        assert_eq!(field.transpose(), res);
+    fn test_wide_and_ragged() {
+        let row = format!("{}O{}#O", ".".repeat(130), ".".repeat(67));
+        let field = Field::from_str(&format!("{row}\n{row}")).unwrap();
+        let left = format!("O{}#O", ".".repeat(197));
+        assert_eq!(
+            field.push(Direction::Left),
+            Field::from_str(&format!("{left}\n{left}")).unwrap()
+        );
+        let right = format!("{}O#O", ".".repeat(197));
+        assert_eq!(
+            field.push(Direction::Right),
+            Field::from_str(&format!("{right}\n{right}")).unwrap()
+        );
+        assert_eq!(field.push(Direction::Down).load(Direction::Up), 6);
+
+        assert!(Field::from_str(&format!("O.\n{}", ".".repeat(130))).is_err());
+        assert!(Field::from_str(&format!("{}\nO.", ".".repeat(130))).is_err());
     }
 
     #[test]
@@ -216,7 +357,7 @@ O.#..O.#.#
 #...O###..
 #..OO#....";
         let field_1_rot = Field::from_str(input_after_1_cycle).unwrap();
-        assert_eq!(field.cycle().field, field_1_rot.field);
+        assert_eq!(field.cycle(), field_1_rot);
         let input = ".....#....
 ....#...O#
 .....##...
@@ -227,10 +368,7 @@ O.#..O.#.#
 .......OOO
 #..OO###..
 #.OOO#...O";
-        assert_eq!(
-            field.cycle().cycle().field,
-            Field::from_str(input).unwrap().field
-        );
+        assert_eq!(field.cycle().cycle(), Field::from_str(input).unwrap());
     }
 
     #[test]
@@ -269,27 +407,54 @@ O..#.OO...
         let origin = Field::from_str(input).unwrap();
         let up_push = Field::from_str(input_up).unwrap();
         let right_push = Field::from_str(input_right).unwrap();
-        assert_eq!(origin.push(Direction::Up).field, up_push.field);
-        assert_eq!(origin.push(Direction::Right).field, right_push.field);
+        assert_eq!(origin.push(Direction::Up), up_push);
+        assert_eq!(origin.push(Direction::Right), right_push);
     }
 
     #[test]
     fn test_push_right() {
-        let v = vec![
-            Item::Empty,
-            Item::Round,
-            Item::Empty,
-            Item::Rock,
-            Item::Empty,
-        ];
-        let right = vec![
-            Item::Empty,
-            Item::Empty,
-            Item::Round,
-            Item::Rock,
-            Item::Empty,
-        ];
-        assert_eq!(right, push_row_right(&v));
+        let field = Field::from_str(".O.#.").unwrap();
+        let right = Field::from_str("..O#.").unwrap();
+        assert_eq!(right, field.push(Direction::Right));
+        let field = Field::from_str("O.O.#OO.O").unwrap();
+        let right = Field::from_str("..OO#.OOO").unwrap();
+        assert_eq!(right, field.push(Direction::Right));
+    }
+
+    #[test]
+    fn test_program() {
+        let input = "O....#....
+O.OO#....#
+.....##...
+OO.#O....O
+.O.....O#.
+O.#..O.#.#
+..O..#O..O
+.......O..
+#....###..
+#OO..#....";
+        let field = Field::from_str(input).unwrap();
+        let spin = Program::from_str("NWSE").unwrap();
+        assert_eq!(field.run(&spin, 2), field.cycle().cycle());
+        assert_eq!(field.run(&spin, 1_000_000_000).load(Direction::Up), 64);
+        assert!(Program::from_str("NX").is_err());
+
+        let north = field.run(&Program::from_str("N").unwrap(), 3);
+        assert_eq!(north.load(Direction::Up), 136);
+        assert_eq!(
+            north.load_distribution(Direction::Up),
+            vec![34, 27, 17, 10, 8, 7, 7, 14, 0, 12]
+        );
+    }
+
+    #[test]
+    fn test_load_sides() {
+        let field = Field::from_str("O..\n..#\n.O.").unwrap();
+        assert_eq!(field.load(Direction::Up), 3 + 1);
+        assert_eq!(field.load(Direction::Down), 1 + 3);
+        assert_eq!(field.load(Direction::Left), 3 + 2);
+        assert_eq!(field.load(Direction::Right), 1 + 2);
+        assert_eq!(field.load_distribution(Direction::Left), vec![3, 0, 2]);
     }
 
     #[test]